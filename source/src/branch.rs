@@ -19,9 +19,9 @@ use std::fmt;
 
 use serde::{Deserialize, Serialize};
 
-use radicle_surf::vcs::git::{self, Browser, RefScope};
+use radicle_surf::vcs::git::{self, BranchSort, Browser, RefScope};
 
-use crate::error::Error;
+use crate::{commit::Header, error::Error};
 
 /// Branch name representation.
 #[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd, Deserialize, Serialize)]
@@ -63,20 +63,92 @@ pub fn branches(browser: &Browser<'_>, filter: RefScope) -> Result<Vec<Branch>,
     Ok(branches)
 }
 
+/// A branch along with its head commit and upstream tracking information.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BranchInfo {
+    /// The name of the branch, prefixed with the remote for remote branches.
+    pub name: Branch,
+    /// The header of the commit the branch points to.
+    pub head: Header,
+    /// The upstream branch, prefixed with its remote, if one is configured.
+    pub upstream: Option<Branch>,
+    /// The number of commits on the branch that are not on its upstream.
+    pub ahead: Option<usize>,
+    /// The number of commits on the upstream that are not on the branch.
+    pub behind: Option<usize>,
+    /// The committer time of the head commit, in seconds since the epoch.
+    pub last_activity: i64,
+}
+
+impl From<git::BranchInfo> for BranchInfo {
+    fn from(info: git::BranchInfo) -> Self {
+        Self {
+            last_activity: info.last_activity().seconds(),
+            name: Branch(info.branch.name()),
+            head: Header::from(&info.head),
+            upstream: info.upstream.map(|upstream| Branch(upstream.name())),
+            ahead: info.ahead_behind.map(|ahead_behind| ahead_behind.ahead),
+            behind: info.ahead_behind.map(|ahead_behind| ahead_behind.behind),
+        }
+    }
+}
+
+/// Given a project id to a repo returns the list of branches along with their
+/// head commit and upstream, sorted as given by `sort`.
+///
+/// # Errors
+///
+/// Will return [`Error`] if the project doesn't exist or the surf interaction
+/// fails.
+pub fn branch_info(
+    browser: &Browser<'_>,
+    filter: RefScope,
+    sort: BranchSort,
+) -> Result<Vec<BranchInfo>, Error> {
+    Ok(browser
+        .list_branch_info(filter, sort)?
+        .into_iter()
+        .map(BranchInfo::from)
+        .collect())
+}
+
 /// Information about a locally checked out repository.
 #[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct LocalState {
     /// List of branches.
     branches: Vec<Branch>,
+    /// The branch `HEAD` points to, if it points to one.
+    default_branch: Option<Branch>,
 }
 
 /// Given a path to a repo returns the list of branches and if it is managed by
 /// coco.
 ///
+/// The branches are listed from `default_branch`, or from the alphabetically
+/// smallest branch if there is no such branch. See [`local_state_from_head`]
+/// for browsing the branch `HEAD` points to instead.
+///
 /// # Errors
 ///
 /// Will return [`Error`] if the repository doesn't exist.
 pub fn local_state(repo_path: &str, default_branch: &str) -> Result<LocalState, Error> {
+    browse_local_state(repo_path, Some(default_branch))
+}
+
+/// Given a path to a repo returns the list of branches, like [`local_state`],
+/// browsing the branch `HEAD` points to. If `HEAD` is detached, the
+/// alphabetically smallest branch is browsed instead.
+///
+/// # Errors
+///
+/// Will return [`Error`] if the repository doesn't exist.
+pub fn local_state_from_head(repo_path: &str) -> Result<LocalState, Error> {
+    browse_local_state(repo_path, None)
+}
+
+fn browse_local_state(repo_path: &str, default_branch: Option<&str>) -> Result<LocalState, Error> {
     let repo = git2::Repository::open(repo_path).map_err(git::error::Error::from)?;
     let first_branch = repo
         .branches(Some(git2::BranchType::Local))
@@ -91,10 +163,15 @@ pub fn local_state(repo_path: &str, default_branch: &str) -> Result<LocalState,
 
     let repo = git::Repository::new(repo_path)?;
 
-    let browser = match Browser::new(&repo, git::Branch::local(default_branch)) {
+    let browser = match default_branch {
+        Some(default_branch) => Browser::new(&repo, git::Branch::local(default_branch)),
+        None => Browser::default(&repo),
+    };
+    let browser = match browser {
         Ok(browser) => browser,
         Err(_) => Browser::new(&repo, git::Branch::local(&first_branch))?,
     };
+    let default_branch = browser.default_branch().ok().map(Branch::from);
 
     let mut branches = browser
         .list_branches(RefScope::Local)?
@@ -104,5 +181,8 @@ pub fn local_state(repo_path: &str, default_branch: &str) -> Result<LocalState,
 
     branches.sort();
 
-    Ok(LocalState { branches })
+    Ok(LocalState {
+        branches,
+        default_branch,
+    })
 }
@@ -27,7 +27,7 @@ use radicle_surf::{
     vcs::git::{self, Browser, Rev},
 };
 
-use crate::{branch::Branch, error::Error, person::Person, revision::Revision};
+use crate::{branch::Branch, error::Error, person::Person, revision::Revision, tag::Tag};
 
 /// Commit statistics.
 #[derive(Clone, Serialize)]
@@ -49,6 +49,8 @@ pub struct Commit {
     pub diff: diff::Diff,
     /// The list of branches this commit belongs to.
     pub branches: Vec<Branch>,
+    /// The list of tags this commit belongs to.
+    pub tags: Vec<Tag>,
 }
 
 /// Representation of a code commit.
@@ -68,17 +70,47 @@ pub struct Header {
     /// The recorded time of the committer signature. This is a convenience
     /// alias until we expose the actual author and commiter signatures.
     pub committer_time: git2::Time,
+    /// The trailers at the end of the commit message, e.g. `Signed-off-by`.
+    pub trailers: Vec<git::Trailer>,
+    /// The additional authors credited through `Co-authored-by` trailers.
+    pub co_authors: Vec<Person>,
+    /// The notes attached to the commit. These are only filled in by
+    /// [`commit_with_notes`] and [`header_with_notes`].
+    pub notes: Vec<Note>,
+}
+
+/// A note attached to a commit, e.g. CI results kept under `refs/notes/ci`.
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Note {
+    /// The notes ref the note was found under, e.g. `refs/notes/commits`.
+    pub notes_ref: String,
+    /// The author of the note.
+    pub author: Person,
+    /// The message of the note.
+    pub message: String,
+}
+
+impl From<git::Note> for Note {
+    fn from(note: git::Note) -> Self {
+        Self {
+            notes_ref: note.notes_ref,
+            author: Person {
+                name: note.author.name,
+                email: note.author.email,
+            },
+            message: note.message,
+        }
+    }
 }
 
 impl Header {
     /// Returns the commit description text. This is the text after the one-line
-    /// summary.
+    /// summary, without the trailers.
     #[must_use]
     pub fn description(&self) -> &str {
-        self.message
-            .strip_prefix(&self.summary)
-            .unwrap_or(&self.message)
-            .trim()
+        let (body, _) = git::commit::split_trailers(&self.message);
+        body.strip_prefix(&self.summary).unwrap_or(body).trim()
     }
 }
 
@@ -97,6 +129,16 @@ impl From<&git::Commit> for Header {
                 email: commit.committer.email.clone(),
             },
             committer_time: commit.committer.time,
+            trailers: commit.trailers.clone(),
+            co_authors: commit
+                .co_authors()
+                .into_iter()
+                .map(|author| Person {
+                    name: author.name,
+                    email: author.email,
+                })
+                .collect(),
+            notes: vec![],
         }
     }
 }
@@ -106,13 +148,16 @@ impl Serialize for Header {
     where
         S: Serializer,
     {
-        let mut state = serializer.serialize_struct("Header", 6)?;
+        let mut state = serializer.serialize_struct("Header", 9)?;
         state.serialize_field("sha1", &self.sha1.to_string())?;
         state.serialize_field("author", &self.author)?;
         state.serialize_field("summary", &self.summary)?;
         state.serialize_field("description", &self.description())?;
         state.serialize_field("committer", &self.committer)?;
         state.serialize_field("committerTime", &self.committer_time.seconds())?;
+        state.serialize_field("trailers", &self.trailers)?;
+        state.serialize_field("coAuthors", &self.co_authors)?;
+        state.serialize_field("notes", &self.notes)?;
         state.end()
     }
 }
@@ -191,6 +236,14 @@ pub fn commit(browser: &mut Browser<'_>, sha1: git2::Oid) -> Result<Commit, Erro
         .map(Branch::from)
         .collect();
 
+    let mut tags: Vec<Tag> = browser
+        .revision_tags(sha1)?
+        .into_iter()
+        .map(|tag| Tag::from(tag.name().to_string()))
+        .collect();
+    tags.sort();
+    tags.dedup();
+
     Ok(Commit {
         header: Header::from(commit),
         stats: Stats {
@@ -199,6 +252,7 @@ pub fn commit(browser: &mut Browser<'_>, sha1: git2::Oid) -> Result<Commit, Erro
         },
         diff,
         branches,
+        tags,
     })
 }
 
@@ -217,6 +271,37 @@ pub fn header(browser: &mut Browser<'_>, sha1: git2::Oid) -> Result<Header, Erro
     Ok(Header::from(commit))
 }
 
+/// Retrieves the [`Commit`] for the given `sha1`, like [`commit`], along with
+/// the notes attached to it.
+///
+/// # Errors
+///
+/// Will return [`Error`] if the project doesn't exist or the surf interaction
+/// fails.
+pub fn commit_with_notes(browser: &mut Browser<'_>, sha1: git2::Oid) -> Result<Commit, Error> {
+    let mut commit = commit(browser, sha1)?;
+    commit.header.notes = notes(browser, sha1)?;
+    Ok(commit)
+}
+
+/// Retrieves the [`Header`] for the given `sha1`, like [`header`], along with
+/// the notes attached to it.
+///
+/// # Errors
+///
+/// Will return [`Error`] if the project doesn't exist or the surf interaction
+/// fails.
+pub fn header_with_notes(browser: &mut Browser<'_>, sha1: git2::Oid) -> Result<Header, Error> {
+    let mut header = header(browser, sha1)?;
+    header.notes = notes(browser, sha1)?;
+    Ok(header)
+}
+
+/// Retrieves the notes attached to the commit `sha1`, across all notes refs.
+fn notes(browser: &Browser<'_>, sha1: git2::Oid) -> Result<Vec<Note>, Error> {
+    Ok(browser.notes(sha1)?.into_iter().map(Note::from).collect())
+}
+
 /// Retrieves the [`Commit`] history for the given `revision`.
 ///
 /// # Errors
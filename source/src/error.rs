@@ -36,10 +36,25 @@ pub enum Error {
     #[error(transparent)]
     Git(#[from] git::error::Error),
 
+    /// A revision expression could not be parsed.
+    #[error(transparent)]
+    Revision(#[from] git::ParseError),
+
     /// When trying to query a repositories branches, but there are none.
     #[error("the repository has no branches")]
     NoBranches,
 
+    /// An asynchronous operation did not finish in time.
+    #[cfg(feature = "async")]
+    #[error("the operation did not finish within {0:?}")]
+    Timeout(std::time::Duration),
+
+    /// An asynchronous operation was cancelled before it finished, e.g.
+    /// because the runtime shut down.
+    #[cfg(feature = "async")]
+    #[error("the operation was cancelled")]
+    Cancelled,
+
     /// Trying to find a file path which could not be found.
     #[error("the path '{0}' was not found")]
     PathNotFound(file_system::Path),
@@ -0,0 +1,51 @@
+// This file is part of radicle-surf
+// <https://github.com/radicle-dev/radicle-surf>
+//
+// Copyright (C) 2019-2020 The Radicle Team <dev@radicle.xyz>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 or
+// later as published by the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Browsing from multi-threaded servers, where a [`Browser`] cannot be held
+//! across threads or `.await` points.
+
+use std::{path::PathBuf, sync::Arc};
+
+use radicle_surf::vcs::git::Browser;
+pub use radicle_surf::vcs::git::{BrowserHandle, RepositoryPool};
+
+use crate::error::Error;
+
+/// Create a [`BrowserHandle`] on the default branch of the repository at
+/// `path`, backed by a new [`RepositoryPool`].
+///
+/// # Errors
+///
+/// Will return [`Error`] if the repository cannot be opened.
+pub fn handle(path: impl Into<PathBuf>) -> Result<BrowserHandle, Error> {
+    let pool = RepositoryPool::new(path)?;
+    Ok(BrowserHandle::new(Arc::new(pool)))
+}
+
+/// Run `f`, typically one of the functions of this crate, with a [`Browser`]
+/// built from `handle`, e.g.
+/// `browse(&handle, |browser| commits(browser, None::<Revision<String>>))`.
+///
+/// # Errors
+///
+/// Will return [`Error`] if the browser cannot be built, or if `f` fails.
+pub fn browse<T, F>(handle: &BrowserHandle, f: F) -> Result<T, Error>
+where
+    F: FnOnce(&mut Browser<'_>) -> Result<T, Error>,
+{
+    handle.with_browser(f)
+}
@@ -0,0 +1,86 @@
+// This file is part of radicle-surf
+// <https://github.com/radicle-dev/radicle-surf>
+//
+// Copyright (C) 2019-2020 The Radicle Team <dev@radicle.xyz>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 or
+// later as published by the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::convert::TryFrom as _;
+
+use radicle_surf::{
+    file_system::{self, language::detect, File},
+    vcs::git::{Browser, Rev},
+};
+
+use crate::{error::Error, revision::Revision};
+
+pub use file_system::Language;
+
+/// Syntaxes known to [`crate::SYNTAX_SET`] that are prose or data rather than
+/// code, and so are left out of the breakdown.
+#[cfg(feature = "syntax")]
+const NON_CODE: &[&str] = &[
+    "Plain Text",
+    "Markdown",
+    "MultiMarkdown",
+    "reStructuredText",
+    "JSON",
+    "YAML",
+    "TOML",
+    "XML",
+    "Diff",
+];
+
+/// Guess the language of a file, falling back to the syntaxes used for
+/// highlighting when the `syntax` feature is enabled.
+fn classify(path: &str, file: &File) -> Option<String> {
+    if let Some(language) = detect(path, &file.contents) {
+        return Some(language.to_string());
+    }
+
+    #[cfg(feature = "syntax")]
+    {
+        let syntax = std::path::Path::new(path)
+            .extension()
+            .and_then(std::ffi::OsStr::to_str)
+            .and_then(|ext| crate::SYNTAX_SET.find_syntax_by_extension(ext))?;
+        if !NON_CODE.contains(&syntax.name.as_str()) {
+            return Some(syntax.name.clone());
+        }
+    }
+
+    None
+}
+
+/// Retrieve the language breakdown of the snapshot at the given `revision`,
+/// sorted from the most to the least used language.
+///
+/// # Errors
+///
+/// Will return [`Error`] if any of the surf interactions fail.
+pub fn languages<P>(
+    browser: &mut Browser<'_>,
+    maybe_revision: Option<Revision<P>>,
+) -> Result<Vec<Language>, Error>
+where
+    P: ToString,
+{
+    let maybe_revision = maybe_revision.map(Rev::try_from).transpose()?;
+
+    if let Some(revision) = maybe_revision {
+        browser.rev(revision)?;
+    }
+
+    let root = browser.get_directory()?;
+    Ok(root.languages_with(classify))
+}
@@ -22,14 +22,27 @@
 pub use radicle_surf as surf;
 
 pub mod branch;
-pub use branch::{branches, local_state, Branch, LocalState};
+pub use branch::{
+    branch_info, branches, local_state, local_state_from_head, Branch, BranchInfo, LocalState,
+};
 
 pub mod commit;
-pub use commit::{commit, commits, Commit};
+pub use commit::{commit, commit_with_notes, commits, Commit};
 
 pub mod error;
 pub use error::Error;
 
+pub mod handle;
+pub use handle::{BrowserHandle, RepositoryPool};
+
+#[cfg(feature = "async")]
+pub mod nonblocking;
+#[cfg(feature = "async")]
+pub use nonblocking::AsyncBrowser;
+
+pub mod language;
+pub use language::{languages, Language};
+
 pub mod object;
 pub use object::{blob, tree, Blob, BlobContent, Info, ObjectType, Tree};
 
@@ -48,4 +61,4 @@ pub mod syntax;
 pub use syntax::SYNTAX_SET;
 
 pub mod tag;
-pub use tag::{tags, Tag};
+pub use tag::{tag_info, tags, tags_in, Tag, TagInfo};
@@ -0,0 +1,305 @@
+// This file is part of radicle-surf
+// <https://github.com/radicle-dev/radicle-surf>
+//
+// Copyright (C) 2019-2020 The Radicle Team <dev@radicle.xyz>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 or
+// later as published by the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Async equivalents of the functions of this crate, enabled by the `async`
+//! feature.
+//!
+//! The functions run on tokio's blocking thread pool, so they must be called
+//! from within a tokio runtime, which needs the time driver enabled for
+//! timeouts. When the returned future is dropped, because it was cancelled or
+//! timed out, work that has not started yet is skipped. Work that has already
+//! started cannot be interrupted, so it finishes in the background and its
+//! result is discarded.
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use radicle_surf::vcs::git::{Browser, BrowserHandle, RefScope};
+
+use crate::{
+    branch::{self, Branch},
+    commit::{self, Commit, Commits},
+    error::Error,
+    object::{self, Blob, Tree},
+    revision::Revision,
+    tag::{self, Tag},
+};
+
+/// Runs the functions of this crate on the blocking thread pool, with
+/// browsers built from a [`BrowserHandle`].
+#[derive(Clone, Debug)]
+pub struct AsyncBrowser {
+    handle: BrowserHandle,
+    timeout: Option<Duration>,
+}
+
+impl AsyncBrowser {
+    /// Browse what `handle` describes, without a timeout.
+    #[must_use]
+    pub fn new(handle: BrowserHandle) -> Self {
+        Self {
+            handle,
+            timeout: None,
+        }
+    }
+
+    /// Fail the operations that take longer than `timeout` with
+    /// [`Error::Timeout`].
+    #[must_use]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Run `f` with a [`Browser`] on the blocking thread pool.
+    ///
+    /// # Errors
+    ///
+    /// Will return [`Error::Timeout`] if `f` did not finish in time,
+    /// [`Error::Cancelled`] if the runtime shut down, and any error of `f`
+    /// otherwise.
+    ///
+    /// # Panics
+    ///
+    /// Resumes the panic of `f`, if it panicked.
+    pub async fn run<T, F>(&self, f: F) -> Result<T, Error>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut Browser<'_>) -> Result<T, Error> + Send + 'static,
+    {
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let _guard = CancelOnDrop(cancelled.clone());
+        let handle = self.handle.clone();
+        let task = tokio::task::spawn_blocking(move || {
+            if cancelled.load(Ordering::Acquire) {
+                return Err(Error::Cancelled);
+            }
+            handle.with_browser(f)
+        });
+
+        let joined = match self.timeout {
+            Some(timeout) => tokio::time::timeout(timeout, task)
+                .await
+                .map_err(|_| Error::Timeout(timeout))?,
+            None => task.await,
+        };
+
+        match joined {
+            Ok(result) => result,
+            Err(err) if err.is_panic() => std::panic::resume_unwind(err.into_panic()),
+            Err(_) => Err(Error::Cancelled),
+        }
+    }
+
+    /// See [`object::blob`].
+    ///
+    /// # Errors
+    ///
+    /// See [`AsyncBrowser::run`].
+    pub async fn blob<P>(
+        &self,
+        maybe_revision: Option<Revision<P>>,
+        path: String,
+    ) -> Result<Blob, Error>
+    where
+        P: ToString + Send + 'static,
+    {
+        self.run(move |browser| object::blob(browser, maybe_revision, &path))
+            .await
+    }
+
+    /// See [`object::tree`].
+    ///
+    /// # Errors
+    ///
+    /// See [`AsyncBrowser::run`].
+    pub async fn tree<P>(
+        &self,
+        maybe_revision: Option<Revision<P>>,
+        maybe_prefix: Option<String>,
+    ) -> Result<Tree, Error>
+    where
+        P: ToString + Send + 'static,
+    {
+        self.run(move |browser| object::tree(browser, maybe_revision, maybe_prefix))
+            .await
+    }
+
+    /// See [`commit::commit`].
+    ///
+    /// # Errors
+    ///
+    /// See [`AsyncBrowser::run`].
+    pub async fn commit(&self, sha1: git2::Oid) -> Result<Commit, Error> {
+        self.run(move |browser| commit::commit(browser, sha1)).await
+    }
+
+    /// See [`commit::commit_with_notes`].
+    ///
+    /// # Errors
+    ///
+    /// See [`AsyncBrowser::run`].
+    pub async fn commit_with_notes(&self, sha1: git2::Oid) -> Result<Commit, Error> {
+        self.run(move |browser| commit::commit_with_notes(browser, sha1))
+            .await
+    }
+
+    /// See [`commit::commits`].
+    ///
+    /// # Errors
+    ///
+    /// See [`AsyncBrowser::run`].
+    pub async fn commits<P>(&self, maybe_revision: Option<Revision<P>>) -> Result<Commits, Error>
+    where
+        P: ToString + Send + 'static,
+    {
+        self.run(move |browser| commit::commits(browser, maybe_revision))
+            .await
+    }
+
+    /// See [`branch::branches`].
+    ///
+    /// # Errors
+    ///
+    /// See [`AsyncBrowser::run`].
+    pub async fn branches(&self, filter: RefScope) -> Result<Vec<Branch>, Error> {
+        self.run(move |browser| branch::branches(browser, filter))
+            .await
+    }
+
+    /// See [`tag::tags`].
+    ///
+    /// # Errors
+    ///
+    /// See [`AsyncBrowser::run`].
+    pub async fn tags(&self) -> Result<Vec<Tag>, Error> {
+        self.run(|browser| tag::tags(browser)).await
+    }
+}
+
+/// Marks the work of a future as cancelled when the future is dropped.
+struct CancelOnDrop(Arc<AtomicBool>);
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::Release);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handle;
+    use radicle_surf::{
+        file_system::Directory,
+        vcs::git::{ephemeral::EphemeralRepository, Branch as GitBranch},
+    };
+
+    fn repository() -> Result<EphemeralRepository, Error> {
+        let repo = EphemeralRepository::new()?;
+        repo.commit(GitBranch::local("master"), &Directory::root(), "Initial")?;
+        repo.set_head(GitBranch::local("master"))?;
+        Ok(repo)
+    }
+
+    #[tokio::test]
+    async fn runs_on_the_blocking_pool() -> Result<(), Error> {
+        let repo = repository()?;
+        let browser = AsyncBrowser::new(handle::handle(repo.path())?);
+
+        let commits = browser.commits(None::<Revision<String>>).await?;
+        assert_eq!(commits.headers.len(), 1);
+        assert_eq!(commits.headers[0].summary, "Initial");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn times_out() -> Result<(), Error> {
+        let repo = repository()?;
+        let timeout = Duration::from_millis(10);
+        let browser = AsyncBrowser::new(handle::handle(repo.path())?).timeout(timeout);
+
+        let result = browser
+            .run(|_| {
+                std::thread::sleep(Duration::from_millis(200));
+                Ok(())
+            })
+            .await;
+        assert!(matches!(result, Err(Error::Timeout(t)) if t == timeout));
+
+        Ok(())
+    }
+
+    #[test]
+    fn skips_cancelled_work() -> Result<(), Error> {
+        let repo = repository()?;
+        let browser =
+            AsyncBrowser::new(handle::handle(repo.path())?).timeout(Duration::from_millis(10));
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .max_blocking_threads(1)
+            .build()
+            .expect("failed to build the runtime");
+
+        runtime.block_on(async {
+            // Keep the only blocking thread busy until the operation is cancelled.
+            let (release, busy) = std::sync::mpsc::channel::<()>();
+            let busy = tokio::task::spawn_blocking(move || busy.recv());
+
+            let ran = Arc::new(AtomicBool::new(false));
+            let result = browser
+                .run({
+                    let ran = ran.clone();
+                    move |_| {
+                        ran.store(true, Ordering::Release);
+                        Ok(())
+                    }
+                })
+                .await;
+            assert!(matches!(result, Err(Error::Timeout(_))));
+
+            release.send(()).expect("the busy task is waiting");
+            busy.await
+                .expect("the busy task finished")
+                .expect("the busy task was released");
+            // The blocking tasks run in order, so the cancelled one is done too.
+            tokio::task::spawn_blocking(|| ())
+                .await
+                .expect("the last task finished");
+            assert!(!ran.load(Ordering::Acquire));
+        });
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "browsing failed")]
+    async fn resumes_panics() {
+        let repo = repository().expect("failed to create the repository");
+        let browser = AsyncBrowser::new(handle::handle(repo.path()).expect("failed to open"));
+
+        let _ = browser
+            .run(|_| -> Result<(), Error> { panic!("browsing failed") })
+            .await;
+    }
+}
@@ -26,7 +26,7 @@ use crate::{
     branch::{branches, Branch},
     error::Error,
     oid::Oid,
-    tag::{tags, Tag},
+    tag::{tags, tags_in, Tag},
 };
 
 pub enum Category<P, U> {
@@ -58,6 +58,21 @@ pub enum Revision<P> {
         /// The SHA1 value.
         sha: Oid,
     },
+    /// Select the commits reachable from `to` but not from `from`.
+    #[serde(rename_all = "camelCase")]
+    Range {
+        /// The start of the range, which is excluded.
+        from: Box<Revision<P>>,
+        /// The end of the range, which is included.
+        to: Box<Revision<P>>,
+    },
+    /// Select the revision, or revision range, given in git's revision syntax,
+    /// e.g. `v1.0.0..v1.1.0` or `master~2`.
+    #[serde(rename_all = "camelCase")]
+    Expression {
+        /// The revision expression.
+        expression: String,
+    },
 }
 
 impl<P> TryFrom<Revision<P>> for Rev
@@ -79,6 +94,10 @@ where
                 let oid: git2::Oid = sha.into();
                 Ok(oid.into())
             },
+            Revision::Range { from, to } => {
+                Ok(Rev::range(Rev::try_from(*from)?, Rev::try_from(*to)?))
+            },
+            Revision::Expression { expression } => Ok(expression.parse()?),
         }
     }
 }
@@ -98,7 +117,7 @@ pub struct Revisions<P, U> {
 }
 
 /// Provide the [`Revisions`] for the given `peer_id`, looking for the
-/// branches as [`RefScope::Remote`].
+/// branches and tags as [`RefScope::Remote`].
 ///
 /// If there are no branches then this returns `None`.
 ///
@@ -114,14 +133,13 @@ where
     P: Clone + ToString,
 {
     let remote_branches = branches(browser, Some(peer_id.clone()).into())?;
+    let tags = tags_in(browser, Some(peer_id.clone()).into())?;
     Ok(
         NonEmpty::from_vec(remote_branches).map(|branches| Revisions {
             peer_id,
             user,
             branches,
-            // TODO(rudolfs): implement remote peer tags once we decide how
-            // https://radicle.community/t/git-tags/214
-            tags: vec![],
+            tags,
         }),
     )
 }
@@ -19,13 +19,14 @@ use std::fmt;
 
 use serde::Serialize;
 
-use radicle_surf::{git::RefScope, vcs::git::Browser};
+use radicle_surf::{
+    git::RefScope,
+    vcs::git::{self, Browser},
+};
 
-use crate::error::Error;
+use crate::{error::Error, oid::Oid, person::Person};
 
-/// Tag name representation.
-///
-/// We still need full tag support.
+/// Tag name representation. See [`TagInfo`] for the details of a tag.
 #[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd, Serialize)]
 pub struct Tag(pub(crate) String);
 
@@ -41,6 +42,91 @@ impl fmt::Display for Tag {
     }
 }
 
+/// The details of a tag.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagInfo {
+    /// The name of the tag.
+    pub name: Tag,
+    /// The remote the tag was found under, e.g. the peer for
+    /// `refs/remotes/<peer>/tags/<name>`.
+    pub remote: Option<String>,
+    /// The `Oid` of the tag object for annotated tags, and of the tagged
+    /// commit for lightweight tags.
+    pub id: Oid,
+    /// The `Oid` of the tagged object.
+    pub target: Oid,
+    /// The type of the tagged object.
+    pub target_kind: git::ObjectKind,
+    /// The `Oid` of the object found by following the chain of tags, which is
+    /// `target` unless the tag tags another tag.
+    pub peeled: Oid,
+    /// The type of the object at `peeled`. Only tags of commits can be
+    /// browsed.
+    pub peeled_kind: git::ObjectKind,
+    /// Whether the tag is an annotated tag, as opposed to a lightweight one.
+    pub annotated: bool,
+    /// The tagger of an annotated tag.
+    pub tagger: Option<Person>,
+    /// The message of an annotated tag.
+    pub message: Option<String>,
+    /// Whether the message of an annotated tag carries a signature. The
+    /// signature is not verified.
+    pub signed: bool,
+}
+
+impl From<git::Tag> for TagInfo {
+    fn from(tag: git::Tag) -> Self {
+        let signed = tag.is_signed();
+        match tag {
+            git::Tag::Light {
+                id,
+                kind,
+                name,
+                remote,
+            } => Self {
+                name: Tag(name.name().to_string()),
+                remote,
+                id: Oid(id),
+                target: Oid(id),
+                target_kind: kind,
+                peeled: Oid(id),
+                peeled_kind: kind,
+                annotated: false,
+                tagger: None,
+                message: None,
+                signed,
+            },
+            git::Tag::Annotated {
+                id,
+                target_id,
+                target_kind,
+                peeled_id,
+                peeled_kind,
+                name,
+                tagger,
+                message,
+                remote,
+            } => Self {
+                name: Tag(name.name().to_string()),
+                remote,
+                id: Oid(id),
+                target: Oid(target_id),
+                target_kind,
+                peeled: Oid(peeled_id),
+                peeled_kind,
+                annotated: true,
+                tagger: tagger.map(|tagger| Person {
+                    name: tagger.name,
+                    email: tagger.email,
+                }),
+                message,
+                signed,
+            },
+        }
+    }
+}
+
 /// Retrieves the list of [`Tag`] for the given project `id`.
 ///
 /// # Errors
@@ -48,7 +134,18 @@ impl fmt::Display for Tag {
 /// Will return [`Error`] if the project doesn't exist or the surf interaction
 /// fails.
 pub fn tags(browser: &Browser<'_>) -> Result<Vec<Tag>, Error> {
-    let tag_names = browser.list_tags(RefScope::Local)?;
+    tags_in(browser, RefScope::Local)
+}
+
+/// Retrieves the list of [`Tag`] within the given `scope`, e.g. the tags of a
+/// remote peer under `refs/remotes/<peer>/tags`.
+///
+/// # Errors
+///
+/// Will return [`Error`] if the project doesn't exist or the surf interaction
+/// fails.
+pub fn tags_in(browser: &Browser<'_>, scope: RefScope) -> Result<Vec<Tag>, Error> {
+    let tag_names = browser.list_tags(scope)?;
     let mut tags: Vec<Tag> = tag_names
         .into_iter()
         .map(|tag_name| Tag(tag_name.name().to_string()))
@@ -58,3 +155,21 @@ pub fn tags(browser: &Browser<'_>) -> Result<Vec<Tag>, Error> {
 
     Ok(tags)
 }
+
+/// Retrieves the details of the tags within the given `scope`, sorted by name.
+///
+/// # Errors
+///
+/// Will return [`Error`] if the project doesn't exist or the surf interaction
+/// fails.
+pub fn tag_info(browser: &Browser<'_>, scope: RefScope) -> Result<Vec<TagInfo>, Error> {
+    let mut tags = browser
+        .list_tags(scope)?
+        .into_iter()
+        .map(TagInfo::from)
+        .collect::<Vec<_>>();
+
+    tags.sort_by(|a, b| (&a.name, &a.remote).cmp(&(&b.name, &b.remote)));
+
+    Ok(tags)
+}
@@ -18,14 +18,13 @@
 use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
 use radicle_surf::{
     file_system::{unsound, Path},
-    vcs::git::{Branch, Browser, Repository},
+    vcs::git::{Browser, Repository},
 };
 
 fn last_commit_comparison(c: &mut Criterion) {
     let repo = Repository::new("./data/git-platinum")
         .expect("Could not retrieve ./data/git-platinum as git repository");
-    let browser =
-        Browser::new(&repo, Branch::local("master")).expect("Could not initialise Browser");
+    let browser = Browser::default(&repo).expect("Could not initialise Browser");
 
     let mut group = c.benchmark_group("Last Commit");
     for path in [
@@ -17,14 +17,19 @@
 
 #![allow(dead_code, unused_variables, missing_docs)]
 
-use std::{cell::RefCell, cmp::Ordering, convert::TryFrom, ops::Deref, rc::Rc, slice};
+#[cfg(feature = "git2")]
+use std::convert::TryFrom;
+use std::{cell::RefCell, cmp::Ordering, ops::Deref, rc::Rc, slice};
 
 #[cfg(feature = "serialize")]
 use serde::{ser, Serialize, Serializer};
 
 use crate::file_system::{Directory, DirectoryContents, Path};
 
+#[cfg(feature = "git2")]
 pub mod git;
+#[cfg(feature = "gix")]
+pub(crate) mod lines;
 
 #[cfg_attr(
     feature = "serialize",
@@ -164,6 +169,7 @@ impl<'a> Iterator for IterHunks<'a> {
     }
 }
 
+#[cfg(feature = "git2")]
 impl TryFrom<git2::Patch<'_>> for Hunks {
     type Error = git::error::Hunk;
 
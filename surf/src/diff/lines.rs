@@ -0,0 +1,289 @@
+// This file is part of radicle-surf
+// <https://github.com/radicle-dev/radicle-surf>
+//
+// Copyright (C) 2019-2020 The Radicle Team <dev@radicle.xyz>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 or
+// later as published by the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The line diff of the contents of two files, for backends that do not
+//! compute patches themselves.
+//!
+//! The changes are found by the blob diff of `gix`, with the Myers algorithm
+//! and the indent heuristic of `git diff`, and laid out in hunks as `git diff`
+//! does, with three lines of context and the same headers.
+
+use super::{EofNewLine, Hunk, Hunks, Line, LineDiff};
+use gix::diff::blob::{Algorithm, Diff, InternedInput};
+
+/// The number of unchanged lines shown around the changes of a hunk.
+const CONTEXT: usize = 3;
+
+/// Whether `content` is binary, which `git` decides by looking for a `NUL`
+/// byte within its first 8000 bytes.
+pub(crate) fn is_binary(content: &[u8]) -> bool {
+    content.iter().take(8000).any(|byte| *byte == 0)
+}
+
+/// The hunks turning `old` into `new`, along with the files missing a newline
+/// at their end, if their last line is part of a hunk.
+pub(crate) fn hunks(old: &[u8], new: &[u8]) -> (Hunks, Option<EofNewLine>) {
+    let edits = edits(old, new);
+    let old = lines(old);
+    let new = lines(new);
+
+    let changes = edits
+        .iter()
+        .enumerate()
+        .filter(|(_, edit)| !matches!(edit, Edit::Equal(..)))
+        .map(|(index, _)| index);
+    let mut groups: Vec<(usize, usize)> = Vec::new();
+    for index in changes {
+        match groups.last_mut() {
+            Some((_, last)) if index - *last <= 2 * CONTEXT + 1 => *last = index,
+            _ => groups.push((index, index)),
+        }
+    }
+
+    let mut hunks = Vec::new();
+    let (mut old_missing, mut new_missing) = (false, false);
+    let mut func: Option<&[u8]> = None;
+    let mut searched = None;
+    for (first, last) in groups {
+        let edits = &edits[first.saturating_sub(CONTEXT)..(last + 1 + CONTEXT).min(edits.len())];
+        let (old_start, new_start) = edits[0].start();
+
+        let mut lines = Vec::with_capacity(edits.len());
+        let (mut old_count, mut new_count) = (0, 0);
+        for edit in edits {
+            match *edit {
+                Edit::Equal(o, n) => {
+                    old_missing |= o + 1 == old.len() && !ends_with_newline(old[o]);
+                    new_missing |= n + 1 == new.len() && !ends_with_newline(new[n]);
+                    lines.push(LineDiff::context(old[o].to_vec(), number(o), number(n)));
+                    old_count += 1;
+                    new_count += 1;
+                },
+                Edit::Delete(o, _) => {
+                    old_missing |= o + 1 == old.len() && !ends_with_newline(old[o]);
+                    lines.push(LineDiff::deletion(old[o].to_vec(), number(o)));
+                    old_count += 1;
+                },
+                Edit::Insert(_, n) => {
+                    new_missing |= n + 1 == new.len() && !ends_with_newline(new[n]);
+                    lines.push(LineDiff::addition(new[n].to_vec(), number(n)));
+                    new_count += 1;
+                },
+            }
+        }
+
+        // As `git`, name the nearest line before the hunk which looks like
+        // the start of a function, keeping the name of the previous hunk if
+        // there is none in between.
+        if let Some(found) = (0..old_start)
+            .rev()
+            .take_while(|index| Some(*index) != searched)
+            .find_map(|index| function_name(old[index]))
+        {
+            func = Some(found);
+        }
+        searched = old_start.checked_sub(1);
+
+        let mut header = format!(
+            "@@ -{} +{} @@",
+            range(old_start, old_count),
+            range(new_start, new_count)
+        )
+        .into_bytes();
+        if let Some(func) = func {
+            header.push(b' ');
+            header.extend_from_slice(func);
+        }
+        header.push(b'\n');
+
+        hunks.push(Hunk {
+            header: Line(header),
+            lines,
+        });
+    }
+
+    let eof = match (old_missing, new_missing) {
+        (true, true) => Some(EofNewLine::BothMissing),
+        (true, false) => Some(EofNewLine::OldMissing),
+        (false, true) => Some(EofNewLine::NewMissing),
+        (false, false) => None,
+    };
+    (Hunks(hunks), eof)
+}
+
+/// A step of the edit script turning the old lines into the new ones, at the
+/// indices of the old and new lines it is at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Edit {
+    Equal(usize, usize),
+    Delete(usize, usize),
+    Insert(usize, usize),
+}
+
+impl Edit {
+    fn start(&self) -> (usize, usize) {
+        match *self {
+            Self::Equal(o, n) | Self::Delete(o, n) | Self::Insert(o, n) => (o, n),
+        }
+    }
+}
+
+/// Split `content` into its lines, keeping their line endings.
+fn lines(content: &[u8]) -> Vec<&[u8]> {
+    content.split_inclusive(|byte| *byte == b'\n').collect()
+}
+
+fn ends_with_newline(line: &[u8]) -> bool {
+    line.last() == Some(&b'\n')
+}
+
+/// The line number of the line at `index`.
+fn number(index: usize) -> u32 {
+    index as u32 + 1
+}
+
+/// The range of a hunk header, which names the line before an empty range.
+fn range(start: usize, count: usize) -> String {
+    match count {
+        0 => format!("{},0", start),
+        1 => format!("{}", start + 1),
+        _ => format!("{},{}", start + 1, count),
+    }
+}
+
+/// The name of the function `line` starts, as found by `git` by default: a
+/// line starting with a letter, `_` or `$`, truncated to 80 bytes.
+fn function_name(line: &[u8]) -> Option<&[u8]> {
+    let end = line
+        .iter()
+        .rposition(|byte| !byte.is_ascii_whitespace() && *byte != b'\x0b')?;
+    let line = &line[..=end];
+    match line[0] {
+        first if first.is_ascii_alphabetic() || first == b'_' || first == b'$' => {
+            Some(&line[..line.len().min(80)])
+        },
+        _ => None,
+    }
+}
+
+/// The edit script from `old` to `new`, with the lines of each change
+/// shifted as `git` shifts them.
+fn edits(old: &[u8], new: &[u8]) -> Vec<Edit> {
+    let input = InternedInput::new(old, new);
+    let mut diff = Diff::compute(Algorithm::Myers, &input);
+    diff.postprocess_lines(&input);
+
+    let mut edits = Vec::new();
+    let (mut o, mut n) = (0, 0);
+    for hunk in diff.hunks() {
+        let before = hunk.before.start as usize..hunk.before.end as usize;
+        let after = hunk.after.start as usize..hunk.after.end as usize;
+        edits.extend(
+            (o..before.start)
+                .zip(n..after.start)
+                .map(|(o, n)| Edit::Equal(o, n)),
+        );
+        edits.extend(before.clone().map(|o| Edit::Delete(o, after.start)));
+        edits.extend(after.clone().map(|n| Edit::Insert(before.end, n)));
+        o = before.end;
+        n = after.end;
+    }
+    edits.extend(
+        (o..input.before.len())
+            .zip(n..input.after.len())
+            .map(|(o, n)| Edit::Equal(o, n)),
+    );
+    edits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn header(hunk: &Hunk) -> &str {
+        std::str::from_utf8(&hunk.header.0).expect("the header is not UTF-8")
+    }
+
+    #[test]
+    fn lays_out_hunks_like_git() {
+        let old = b"fn main() {\n    one();\n    two();\n    three();\n    four();\n    five();\n    six();\n    seven();\n    eight();\n    nine();\n    ten();\n    eleven();\n}\n";
+        let new = b"fn main() {\n    zero();\n    one();\n    two();\n    three();\n    four();\n    five();\n    six();\n    seven();\n    eight();\n    nine();\n    ten();\n    twelve();\n}\n";
+        let (diff, eof) = hunks(old, new);
+
+        assert_eq!(eof, None);
+        assert_eq!(
+            diff.iter().map(header).collect::<Vec<_>>(),
+            vec!["@@ -1,4 +1,5 @@\n", "@@ -9,5 +10,5 @@ fn main() {\n"]
+        );
+        assert_eq!(
+            diff.0[1].lines[3..].to_vec(),
+            vec![
+                LineDiff::deletion(b"    eleven();\n".to_vec(), 12),
+                LineDiff::addition(b"    twelve();\n".to_vec(), 13),
+                LineDiff::context(b"}\n".to_vec(), 13, 14),
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_missing_newlines_at_the_end() {
+        let (diff, eof) = hunks(b"a\nb\nc", b"a\nb\nd\n");
+        assert_eq!(eof, Some(EofNewLine::OldMissing));
+        assert_eq!(
+            diff.0[0].lines,
+            vec![
+                LineDiff::context(b"a\n".to_vec(), 1, 1),
+                LineDiff::context(b"b\n".to_vec(), 2, 2),
+                LineDiff::deletion(b"c".to_vec(), 3),
+                LineDiff::addition(b"d\n".to_vec(), 3),
+            ]
+        );
+
+        let (_, eof) = hunks(b"a\n1\n2\n3\n4\n5\nz", b"b\n1\n2\n3\n4\n5\nz");
+        assert_eq!(eof, None);
+    }
+
+    #[test]
+    fn slides_changes_like_git() {
+        let (diff, _) = hunks(b"a\na\nb\n", b"}\na\na\na\n");
+        assert_eq!(
+            diff.0[0].lines,
+            vec![
+                LineDiff::addition(b"}\n".to_vec(), 1),
+                LineDiff::addition(b"a\n".to_vec(), 2),
+                LineDiff::context(b"a\n".to_vec(), 1, 3),
+                LineDiff::context(b"a\n".to_vec(), 2, 4),
+                LineDiff::deletion(b"b\n".to_vec(), 3),
+            ]
+        );
+    }
+
+    #[test]
+    fn creates_and_deletes_whole_files() {
+        let (created, _) = hunks(b"", b"a\nb\n");
+        assert_eq!(
+            created.iter().map(header).collect::<Vec<_>>(),
+            vec!["@@ -0,0 +1,2 @@\n"]
+        );
+        let (deleted, _) = hunks(b"a\n", b"");
+        assert_eq!(
+            deleted.iter().map(header).collect::<Vec<_>>(),
+            vec!["@@ -1 +0,0 @@\n"]
+        );
+    }
+}
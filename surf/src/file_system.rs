@@ -112,7 +112,8 @@
 
 pub mod directory;
 mod error;
+pub mod language;
 pub use error::Error;
 mod path;
 
-pub use self::{directory::*, path::*};
+pub use self::{directory::*, language::Language, path::*};
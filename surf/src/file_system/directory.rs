@@ -25,10 +25,16 @@
 //! an identifier of what type of [`DirectoryContents`] one is viewing when
 //! [listing](#method.list_directory) a directory.
 
-use crate::{file_system::path::*, tree::*};
+use crate::{
+    file_system::{
+        language::{self, Language},
+        path::*,
+    },
+    tree::*,
+};
 use nonempty::NonEmpty;
 use std::{
-    collections::{hash_map::DefaultHasher, HashMap},
+    collections::hash_map::DefaultHasher,
     hash::{Hash, Hasher},
 };
 
@@ -469,6 +475,51 @@ impl Directory {
             .fold(0, |size, file| size + file.size())
     }
 
+    /// Get the language breakdown of a `Directory`, i.e. the number of bytes
+    /// written in each language, sorted from the most to the least used.
+    ///
+    /// Languages are guessed using [`language::detect`]. Vendored and
+    /// generated files are skipped, and the `linguist-vendored`,
+    /// `linguist-generated`, `linguist-documentation`, `linguist-detectable`
+    /// and `linguist-language` attributes of any `.gitattributes` file in the
+    /// `Directory` are honoured.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::file_system::{Directory, File, Language};
+    /// use radicle_surf::file_system::unsound;
+    ///
+    /// let mut root = Directory::root();
+    /// root.insert_file(unsound::path::new("main.rs"), File::new(b"println!(\"Hello, world!\")"));
+    /// root.insert_file(unsound::path::new("lib.rs"), File::new(b"struct Hello(String)"));
+    /// root.insert_file(unsound::path::new("test/eval.hs"), File::new(b"main = pure ()"));
+    /// root.insert_file(unsound::path::new("vendor/dep.hs"), File::new(b"module Dep where"));
+    ///
+    /// assert_eq!(
+    ///     root.languages(),
+    ///     vec![
+    ///         Language { name: "Rust".to_string(), bytes: 45 },
+    ///         Language { name: "Haskell".to_string(), bytes: 14 },
+    ///     ]
+    /// );
+    /// ```
+    ///
+    /// [`language::detect`]: crate::file_system::language::detect
+    pub fn languages(&self) -> Vec<Language> {
+        self.languages_with(|path, file| language::detect(path, &file.contents).map(String::from))
+    }
+
+    /// Get the language breakdown of a `Directory`, like
+    /// [`languages`](#method.languages), but using `classify` to guess the
+    /// language of a file given its path, relative to this `Directory`.
+    pub fn languages_with<F>(&self, classify: F) -> Vec<Language>
+    where
+        F: Fn(&str, &File) -> Option<String>,
+    {
+        language::breakdown(self, classify)
+    }
+
     /// Insert a file into a directory, given the full path to file (file name
     /// inclusive) and the `File` itself.
     ///
@@ -504,7 +555,10 @@ impl Directory {
         }
     }
 
-    pub(crate) fn from_hash_map(files: HashMap<Path, NonEmpty<(Label, File)>>) -> Self {
+    #[cfg(any(feature = "git2", test))]
+    pub(crate) fn from_hash_map(
+        files: std::collections::HashMap<Path, NonEmpty<(Label, File)>>,
+    ) -> Self {
         let mut directory: Self = Directory::root();
 
         for (path, files) in files.into_iter() {
@@ -0,0 +1,530 @@
+// This file is part of radicle-surf
+// <https://github.com/radicle-dev/radicle-surf>
+//
+// Copyright (C) 2019-2020 The Radicle Team <dev@radicle.xyz>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 or
+// later as published by the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Classification of the files of a [`Directory`] by language, which is used
+//! for computing the language breakdown of a snapshot.
+//!
+//! See [`Directory::languages`] and [`Directory::languages_with`].
+
+use crate::file_system::directory::{Directory, DirectoryContents, File};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::HashMap;
+
+#[cfg(feature = "serialize")]
+use serde::Serialize;
+
+/// The number of bytes of a snapshot that are written in a particular
+/// language.
+#[cfg_attr(
+    feature = "serialize",
+    derive(Serialize),
+    serde(rename_all = "camelCase")
+)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Language {
+    /// The name of the language, e.g. `Rust`.
+    pub name: String,
+    /// The total size, in bytes, of the files written in the language.
+    pub bytes: usize,
+}
+
+const FILENAMES: &[(&str, &str)] = &[
+    ("BUILD", "Starlark"),
+    ("BUILD.bazel", "Starlark"),
+    ("CMakeLists.txt", "CMake"),
+    ("Dockerfile", "Dockerfile"),
+    ("GNUmakefile", "Makefile"),
+    ("Gemfile", "Ruby"),
+    ("Makefile", "Makefile"),
+    ("Rakefile", "Ruby"),
+    ("Vagrantfile", "Ruby"),
+    ("WORKSPACE", "Starlark"),
+    ("makefile", "Makefile"),
+];
+
+const EXTENSIONS: &[(&str, &str)] = &[
+    ("bash", "Shell"),
+    ("c", "C"),
+    ("cc", "C++"),
+    ("cjs", "JavaScript"),
+    ("clj", "Clojure"),
+    ("cljs", "Clojure"),
+    ("cmake", "CMake"),
+    ("cpp", "C++"),
+    ("cs", "C#"),
+    ("css", "CSS"),
+    ("cxx", "C++"),
+    ("dart", "Dart"),
+    ("dhall", "Dhall"),
+    ("el", "Emacs Lisp"),
+    ("elm", "Elm"),
+    ("erl", "Erlang"),
+    ("ex", "Elixir"),
+    ("exs", "Elixir"),
+    ("fs", "F#"),
+    ("go", "Go"),
+    ("groovy", "Groovy"),
+    ("h", "C"),
+    ("hh", "C++"),
+    ("hpp", "C++"),
+    ("hs", "Haskell"),
+    ("htm", "HTML"),
+    ("html", "HTML"),
+    ("java", "Java"),
+    ("jl", "Julia"),
+    ("js", "JavaScript"),
+    ("jsx", "JavaScript"),
+    ("kt", "Kotlin"),
+    ("kts", "Kotlin"),
+    ("less", "Less"),
+    ("lhs", "Haskell"),
+    ("lua", "Lua"),
+    ("m", "Objective-C"),
+    ("mjs", "JavaScript"),
+    ("ml", "OCaml"),
+    ("mli", "OCaml"),
+    ("nim", "Nim"),
+    ("nix", "Nix"),
+    ("php", "PHP"),
+    ("pl", "Perl"),
+    ("pm", "Perl"),
+    ("ps1", "PowerShell"),
+    ("purs", "PureScript"),
+    ("py", "Python"),
+    ("r", "R"),
+    ("rb", "Ruby"),
+    ("rkt", "Racket"),
+    ("rs", "Rust"),
+    ("sass", "Sass"),
+    ("scala", "Scala"),
+    ("scss", "SCSS"),
+    ("sh", "Shell"),
+    ("sol", "Solidity"),
+    ("svelte", "Svelte"),
+    ("swift", "Swift"),
+    ("tex", "TeX"),
+    ("ts", "TypeScript"),
+    ("tsx", "TSX"),
+    ("vim", "Vim Script"),
+    ("vue", "Vue"),
+    ("zig", "Zig"),
+    ("zsh", "Shell"),
+];
+
+const INTERPRETERS: &[(&str, &str)] = &[
+    ("bash", "Shell"),
+    ("dash", "Shell"),
+    ("deno", "TypeScript"),
+    ("elixir", "Elixir"),
+    ("escript", "Erlang"),
+    ("ksh", "Shell"),
+    ("lua", "Lua"),
+    ("node", "JavaScript"),
+    ("nodejs", "JavaScript"),
+    ("perl", "Perl"),
+    ("php", "PHP"),
+    ("pwsh", "PowerShell"),
+    ("python", "Python"),
+    ("Rscript", "R"),
+    ("ruby", "Ruby"),
+    ("runghc", "Haskell"),
+    ("runhaskell", "Haskell"),
+    ("sh", "Shell"),
+    ("swift", "Swift"),
+    ("zsh", "Shell"),
+];
+
+fn lookup(table: &[(&str, &'static str)], key: &str) -> Option<&'static str> {
+    table
+        .iter()
+        .find(|(candidate, _)| *candidate == key)
+        .map(|(_, language)| *language)
+}
+
+/// Guess the language of a file given its `path`, relative to the root of the
+/// snapshot, and its `contents`.
+///
+/// Well-known file names, e.g. `Makefile`, are checked first, followed by the
+/// file extension and finally the interpreter named in a `#!` line.
+///
+/// # Examples
+///
+/// ```
+/// use radicle_surf::file_system::language::detect;
+///
+/// assert_eq!(detect("src/lib.rs", b"pub mod diff;"), Some("Rust"));
+/// assert_eq!(detect("Makefile", b"all:"), Some("Makefile"));
+/// assert_eq!(detect("bin/run", b"#!/usr/bin/env python3\nprint()"), Some("Python"));
+/// assert_eq!(detect("README.md", b"# radicle-surf"), None);
+/// ```
+pub fn detect(path: &str, contents: &[u8]) -> Option<&'static str> {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    lookup(FILENAMES, name)
+        .or_else(|| {
+            let (stem, extension) = name.rsplit_once('.')?;
+            if stem.is_empty() {
+                return None;
+            }
+            lookup(EXTENSIONS, extension)
+                .or_else(|| lookup(EXTENSIONS, &extension.to_ascii_lowercase()))
+        })
+        .or_else(|| shebang(contents))
+}
+
+/// Find the language of the interpreter named in the `#!` line of a file.
+fn shebang(contents: &[u8]) -> Option<&'static str> {
+    let line = contents.strip_prefix(b"#!")?;
+    let line = line.split(|byte| *byte == b'\n').next()?;
+    let line = String::from_utf8_lossy(line);
+
+    let mut words = line.split_whitespace();
+    let mut interpreter = words.next()?.rsplit('/').next()?;
+    if interpreter == "env" {
+        // Skip any flags given to `env`, e.g. `-S`.
+        interpreter = words.find(|word| !word.starts_with('-'))?;
+    }
+
+    // Ignore versions, e.g. `python3.9`.
+    let interpreter = interpreter.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.');
+    lookup(INTERPRETERS, interpreter)
+}
+
+/// The state of an attribute for a path, as described in
+/// [gitattributes](https://git-scm.com/docs/gitattributes).
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum State {
+    Set,
+    Unset,
+    Value(String),
+}
+
+impl State {
+    fn as_bool(&self) -> Option<bool> {
+        match self {
+            Self::Set => Some(true),
+            Self::Unset => Some(false),
+            Self::Value(value) => match value.as_str() {
+                "true" => Some(true),
+                "false" => Some(false),
+                _ => None,
+            },
+        }
+    }
+}
+
+/// A line of a `.gitattributes` file, restricted to the `linguist-*`
+/// attributes.
+struct Rule {
+    pattern: Regex,
+    /// The attributes, where `None` means the attribute was made unspecified
+    /// using `!`.
+    attributes: Vec<(String, Option<State>)>,
+}
+
+/// The `linguist-*` attributes from all the `.gitattributes` files of a
+/// snapshot.
+#[derive(Default)]
+struct Attributes {
+    rules: Vec<Rule>,
+}
+
+impl Attributes {
+    /// Add the rules of the `.gitattributes` file found in the directory
+    /// `base`, which is either empty or ends with a `/`.
+    fn add(&mut self, base: &str, contents: &[u8]) {
+        let contents = String::from_utf8_lossy(contents);
+        for line in contents.lines() {
+            let line = line.trim();
+            // Skip comments and macro definitions.
+            if line.is_empty() || line.starts_with('#') || line.starts_with("[attr]") {
+                continue;
+            }
+
+            let mut words = line.split_whitespace();
+            let pattern = match words.next() {
+                Some(pattern) => pattern,
+                None => continue,
+            };
+            let attributes = words
+                .filter_map(|word| {
+                    let (name, state) = if let Some(name) = word.strip_prefix('-') {
+                        (name, Some(State::Unset))
+                    } else if let Some(name) = word.strip_prefix('!') {
+                        (name, None)
+                    } else if let Some((name, value)) = word.split_once('=') {
+                        (name, Some(State::Value(value.to_string())))
+                    } else {
+                        (word, Some(State::Set))
+                    };
+                    if name.starts_with("linguist-") {
+                        Some((name.to_string(), state))
+                    } else {
+                        None
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            if attributes.is_empty() {
+                continue;
+            }
+            if let Ok(pattern) = Regex::new(&pattern_to_regex(base, pattern)) {
+                self.rules.push(Rule {
+                    pattern,
+                    attributes,
+                });
+            }
+        }
+    }
+
+    /// Get the `linguist-*` attributes that apply to `path`, where later rules
+    /// take precedence.
+    fn get(&self, path: &str) -> HashMap<&str, &State> {
+        let mut attributes = HashMap::new();
+        for rule in self.rules.iter().filter(|rule| rule.pattern.is_match(path)) {
+            for (name, state) in &rule.attributes {
+                match state {
+                    Some(state) => attributes.insert(name.as_str(), state),
+                    None => attributes.remove(name.as_str()),
+                };
+            }
+        }
+        attributes
+    }
+}
+
+/// Turn a gitattributes `pattern` found in the directory `base` into a regular
+/// expression matching paths relative to the root of the snapshot.
+fn pattern_to_regex(base: &str, pattern: &str) -> String {
+    let anchored = pattern.trim_end_matches('/').contains('/');
+    let pattern = pattern.trim_start_matches('/');
+
+    let mut re = format!("^{}", regex::escape(base));
+    if !anchored {
+        re.push_str("(?:.*/)?");
+    }
+
+    let mut chars = pattern.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    re.push_str("(?:.*/)?");
+                } else {
+                    re.push_str(".*");
+                }
+            },
+            '*' => re.push_str("[^/]*"),
+            '?' => re.push_str("[^/]"),
+            '[' => {
+                re.push('[');
+                if chars.peek() == Some(&'!') {
+                    chars.next();
+                    re.push('^');
+                }
+                for c in chars.by_ref() {
+                    if c == ']' {
+                        break;
+                    }
+                    if c == '\\' || c == '[' {
+                        re.push('\\');
+                    }
+                    re.push(c);
+                }
+                re.push(']');
+            },
+            c => re.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    re.push('$');
+    re
+}
+
+/// The paths of vendored files, which are excluded from the language
+/// breakdown unless the gitattributes say otherwise.
+static VENDORED: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(^|/)(vendor|vendors|third[-_]?party|node_modules|bower_components|Godeps|\.yarn)/",
+    )
+    .expect("the pattern is valid")
+});
+
+/// The paths of generated files, which are excluded from the language
+/// breakdown unless the gitattributes say otherwise.
+static GENERATED: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(\.min\.(js|css)|\.js\.map|\.css\.map|\.pb\.go|_pb2\.py|\.designer\.cs)$|(^|/)(package-lock\.json|yarn\.lock|Cargo\.lock|Gemfile\.lock|composer\.lock|poetry\.lock|go\.sum)$",
+    )
+    .expect("the pattern is valid")
+});
+
+/// Collect the files of `directory`, keyed by their path relative to the root
+/// of the snapshot.
+fn files(directory: &Directory, prefix: &str, acc: &mut Vec<(String, File)>) {
+    for entry in directory.iter() {
+        match entry {
+            DirectoryContents::File { name, file } => {
+                acc.push((format!("{}{}", prefix, name), file))
+            },
+            DirectoryContents::Directory(sub_directory) => {
+                let prefix = format!("{}{}/", prefix, sub_directory.current());
+                files(&sub_directory, &prefix, acc)
+            },
+        }
+    }
+}
+
+pub(crate) fn breakdown<F>(directory: &Directory, classify: F) -> Vec<Language>
+where
+    F: Fn(&str, &File) -> Option<String>,
+{
+    let mut entries = vec![];
+    files(directory, "", &mut entries);
+
+    // Shallower `.gitattributes` come first, so that deeper ones take
+    // precedence.
+    let mut attribute_files = entries
+        .iter()
+        .filter_map(|(path, file)| {
+            let base = path.strip_suffix(".gitattributes")?;
+            if base.is_empty() || base.ends_with('/') {
+                Some((base, file))
+            } else {
+                None
+            }
+        })
+        .collect::<Vec<_>>();
+    attribute_files.sort_by_key(|(base, _)| base.matches('/').count());
+
+    let mut attributes = Attributes::default();
+    for (base, file) in attribute_files {
+        attributes.add(base, &file.contents);
+    }
+
+    let mut languages: HashMap<String, usize> = HashMap::new();
+    for (path, file) in &entries {
+        let overrides = attributes.get(path);
+        let flag = |name: &str| overrides.get(name).and_then(|state| state.as_bool());
+
+        let excluded = flag("linguist-vendored").unwrap_or_else(|| VENDORED.is_match(path))
+            || flag("linguist-generated").unwrap_or_else(|| GENERATED.is_match(path))
+            || flag("linguist-documentation").unwrap_or(false)
+            || flag("linguist-detectable") == Some(false);
+        if excluded {
+            continue;
+        }
+
+        let language = match overrides.get("linguist-language") {
+            Some(State::Value(language)) => Some(language.clone()),
+            _ => classify(path, file),
+        };
+        if let Some(language) = language {
+            *languages.entry(language).or_insert(0) += file.size();
+        }
+    }
+
+    let mut languages = languages
+        .into_iter()
+        .map(|(name, bytes)| Language { name, bytes })
+        .collect::<Vec<_>>();
+    languages.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.name.cmp(&b.name)));
+    languages
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file_system::unsound;
+
+    #[test]
+    fn test_pattern_to_regex() {
+        let matches = |base: &str, pattern: &str, path: &str| {
+            Regex::new(&pattern_to_regex(base, pattern))
+                .unwrap()
+                .is_match(path)
+        };
+
+        assert!(matches("", "*.rs", "src/lib.rs"));
+        assert!(matches("", "/vendor/**", "vendor/a/b.c"));
+        assert!(!matches("", "/vendor/**", "src/vendor/a.c"));
+        assert!(matches("", "docs/*.html", "docs/index.html"));
+        assert!(!matches("", "docs/*.html", "docs/api/index.html"));
+        assert!(matches("", "**/gen/*.c", "a/b/gen/x.c"));
+        assert!(matches("sub/", "*.c", "sub/dir/x.c"));
+        assert!(!matches("sub/", "*.c", "x.c"));
+        assert!(matches("", "file[0-9].[!c]", "file1.h"));
+    }
+
+    #[test]
+    fn test_exclusions() {
+        assert!(VENDORED.is_match("node_modules/react/index.js"));
+        assert!(VENDORED.is_match("src/third_party/zlib.c"));
+        assert!(!VENDORED.is_match("src/vendoring.rs"));
+        assert!(GENERATED.is_match("web/app.min.js"));
+        assert!(GENERATED.is_match("Cargo.lock"));
+        assert!(!GENERATED.is_match("src/lib.rs"));
+    }
+
+    #[test]
+    fn test_breakdown() {
+        let mut root = Directory::root();
+        root.insert_file(
+            unsound::path::new("src/main.rs"),
+            File::new(b"fn main() {}"),
+        );
+        root.insert_file(unsound::path::new("src/lib.c"), File::new(b"int x;"));
+        root.insert_file(
+            unsound::path::new("vendor/dep/dep.rs"),
+            File::new(b"pub fn dep() {}"),
+        );
+        root.insert_file(unsound::path::new("web/app.min.js"), File::new(b"var a=1;"));
+        root.insert_file(unsound::path::new("web/app.js"), File::new(b"var a = 1;"));
+        root.insert_file(
+            unsound::path::new("scripts/build"),
+            File::new(b"#!/bin/sh\nmake\n"),
+        );
+        root.insert_file(
+            unsound::path::new(".gitattributes"),
+            File::new(b"*.c linguist-language=C++\nweb/* linguist-vendored\nweb/app.js -linguist-vendored\n"),
+        );
+
+        assert_eq!(
+            breakdown(&root, |path, file| detect(path, &file.contents)
+                .map(String::from)),
+            vec![
+                Language {
+                    name: "Shell".to_string(),
+                    bytes: 15,
+                },
+                Language {
+                    name: "Rust".to_string(),
+                    bytes: 12,
+                },
+                Language {
+                    name: "JavaScript".to_string(),
+                    bytes: 10,
+                },
+                Language {
+                    name: "C++".to_string(),
+                    bytes: 6,
+                },
+            ]
+        );
+    }
+}
@@ -15,11 +15,11 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
-use std::fmt::Write as _;
-
 use crate::{file_system::error, nonempty::split_last};
 use nonempty::NonEmpty;
-use std::{convert::TryFrom, ffi::CString, fmt, ops::Deref, path, str::FromStr};
+use std::{convert::TryFrom, fmt, ops::Deref, path, str::FromStr};
+#[cfg(feature = "git2")]
+use std::{ffi::CString, fmt::Write as _};
 
 #[cfg(feature = "serialize")]
 use serde::{Serialize, Serializer};
@@ -166,6 +166,7 @@ impl From<Path> for Vec<Label> {
     }
 }
 
+#[cfg(feature = "git2")]
 impl git2::IntoCString for Path {
     fn into_c_string(self) -> Result<CString, git2::Error> {
         if self.is_root() {
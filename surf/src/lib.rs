@@ -28,7 +28,8 @@
 //!
 //! Let's start surfing (and apologies for the `expect`s):
 //!
-//! ```
+#![cfg_attr(feature = "git2", doc = "```")]
+#![cfg_attr(not(feature = "git2"), doc = "```ignore")]
 //! use radicle_surf::vcs::git;
 //! use radicle_surf::file_system::{Label, Path, SystemType};
 //! use radicle_surf::file_system::unsound;
@@ -40,8 +41,9 @@
 //! // We're going to point to this repo.
 //! let repo = git::Repository::new("./data/git-platinum")?;
 //!
-//! // Here we initialise a new Broswer for a the git repo.
-//! let mut browser = git::Browser::new(&repo, git::Branch::local("master"))?;
+//! // Here we initialise a new Broswer for a the git repo, starting at the
+//! // branch HEAD points to.
+//! let mut browser = git::Browser::default(&repo)?;
 //!
 //! // Set the history to a particular commit
 //! let commit = git::Oid::from_str("80ded66281a4de2889cc07293a8f10947c6d57fe")?;
@@ -90,4 +92,5 @@ pub mod vcs;
 mod nonempty;
 mod tree;
 
+#[cfg(feature = "git2")]
 pub use crate::vcs::git;
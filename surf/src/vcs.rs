@@ -21,8 +21,12 @@
 use crate::file_system::directory::Directory;
 use nonempty::NonEmpty;
 
+#[cfg(feature = "git2")]
 pub mod git;
 
+#[cfg(feature = "gix")]
+pub mod gix;
+
 /// A non-empty bag of artifacts which are used to
 /// derive a [`crate::file_system::Directory`] view. Examples of artifacts
 /// would be commits in Git or patches in Pijul.
@@ -206,6 +210,7 @@ where
     }
 }
 
+#[cfg(feature = "git2")]
 pub(crate) trait GetVcs<Error>
 where
     Self: Sized,
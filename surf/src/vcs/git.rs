@@ -28,7 +28,7 @@
 //!
 //! // Pin the browser to a parituclar commit.
 //! let pin_commit = Oid::from_str("3873745c8f6ffb45c990eb23b491d4b4b6182f95")?;
-//! let mut browser = Browser::new(&repo, Branch::local("master"))?;
+//! let mut browser = Browser::default(&repo)?;
 //! browser.commit(pin_commit)?;
 //!
 //! let directory = browser.get_directory()?;
@@ -67,34 +67,73 @@ pub use git2::{self, Error as Git2Error, Oid, Time};
 
 /// Provides ways of selecting a particular reference/revision.
 mod reference;
-pub use reference::{Ref, Rev};
+pub use reference::{ParseError, Ref, RefInfo, RefPatterns, Rev};
 
 mod repo;
-pub use repo::{History, Repository, RepositoryRef};
+pub use repo::{History, Identities, Repository, RepositoryRef};
 
 pub mod error;
 
 mod ext;
 
+#[cfg(test)]
+mod fixture;
+
 /// Provides the data for talking about branches.
 pub mod branch;
-pub use branch::{Branch, BranchName, BranchType};
+pub use branch::{AheadBehind, Branch, BranchInfo, BranchName, BranchSort, BranchType};
 
 /// Provides the data for talking about tags.
 pub mod tag;
-pub use tag::{Tag, TagName};
+pub use tag::{Description, ObjectKind, Tag, TagName};
 
 /// Provides the data for talking about commits.
 pub mod commit;
-pub use commit::{Author, Commit};
+pub use commit::{Author, Commit, RawCommit, RawSignature, Trailer};
+
+/// Provides the means of creating commits without a working copy.
+pub mod commit_builder;
+pub use commit_builder::CommitBuilder;
+
+/// Provides the data for talking about blame.
+pub mod blame;
+pub use blame::BlameHunk;
+
+/// Provides the data for talking about notes.
+pub mod note;
+pub use note::Note;
+
+/// Provides the data for talking about reflogs.
+pub mod reflog;
+pub use reflog::ReflogEntry;
+
+/// Provides the data for talking about snapshots of references.
+pub mod ref_snapshot;
+pub use ref_snapshot::{RefSnapshot, RefUpdate};
 
 /// Provides the data for talking about namespaces.
 pub mod namespace;
-pub use namespace::Namespace;
+pub use namespace::{Namespace, NamespaceTree};
+
+/// Provides throwaway repositories for tests.
+pub mod ephemeral;
+pub use ephemeral::EphemeralRepository;
+
+/// Provides a cache of results shared by the browsers of a repository.
+pub mod cache;
+pub use cache::{Cache, CacheCounts, CacheLimits, CacheStats};
+
+/// Provides the options for opening a repository.
+pub mod open;
+pub use open::OpenOptions;
+
+/// Provides owned, thread-safe access to a repository.
+pub mod pool;
+pub use pool::{BrowserHandle, PooledRepository, RepositoryPool};
 
 /// Provides the data for talking about repository statistics.
 pub mod stats;
-pub use stats::Stats;
+pub use stats::{Contributor, DetailedStats, Stats, Week};
 
 pub use crate::diff::Diff;
 
@@ -187,6 +226,41 @@ impl<'a> Browser<'a> {
         Ok(Self::init(repository, history))
     }
 
+    /// Create a new browser starting at the default branch of the repository,
+    /// i.e. the branch `HEAD` points to.
+    ///
+    /// # Errors
+    ///
+    /// * [`error::Error::Git`]
+    /// * [`error::Error::NoDefaultBranch`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::vcs::git::{Browser, Oid, Repository};
+    /// use std::str::FromStr;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let browser = Browser::default(&repo)?;
+    ///
+    /// // The HEAD of git-platinum points to `dev`
+    /// assert_eq!(
+    ///     browser.get().first().id,
+    ///     Oid::from_str("27acd68c7504755aa11023300890bb85bbd69d45")?
+    /// );
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn default(repository: impl Into<RepositoryRef<'a>>) -> Result<Self, Error> {
+        let repository = repository.into();
+        let branch = repository.default_branch()?;
+        let history = repository.get_history(branch.into())?;
+        Ok(Self::init(repository, history))
+    }
+
     /// Create a new `Browser` that starts in a given `namespace`.
     ///
     /// # Errors
@@ -238,8 +312,18 @@ impl<'a> Browser<'a> {
 
     fn init(repository: RepositoryRef<'a>, history: History) -> Self {
         let snapshot = Box::new(|repository: &RepositoryRef<'a>, history: &History| {
-            let tree = Self::get_tree(repository.repo_ref, history.0.first())?;
-            Ok(directory::Directory::from_hash_map(tree))
+            let head = history.0.first();
+            let directory = || {
+                let tree = Self::get_tree(repository.repo_ref.0, head)?;
+                Ok(directory::Directory::from_hash_map(tree))
+            };
+            match &repository.cache {
+                Some(cache) => {
+                    let tree = repository.repo_ref.find_commit(head.id)?.tree_id();
+                    cache.tree(tree, directory)
+                },
+                None => directory(),
+            }
         });
         vcs::Browser {
             snapshot,
@@ -275,6 +359,51 @@ impl<'a> Browser<'a> {
             .transpose()
     }
 
+    /// Choose how the author and committer identities of commits are
+    /// resolved, see [`Identities`]. By default they are used as they are
+    /// recorded in the commits. The commits of the current [`History`] are
+    /// converted again with the new setting.
+    ///
+    /// # Errors
+    ///
+    /// * [`error::Error::Git`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::vcs::git::{Branch, Browser, Identities, Repository};
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let mut browser = Browser::new(&repo, Branch::local("master"))?;
+    ///
+    /// // Resolve the identities through the repository's mailmap.
+    /// browser.identities(Identities::Mailmap)?;
+    ///
+    /// // Use the `.mailmap` found on the `master` branch.
+    /// browser.identities(Identities::MailmapAt(Branch::local("master").into()))?;
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn identities(&mut self, identities: Identities) -> Result<(), Error> {
+        self.repository.set_identities(identities);
+        let mut commits = NonEmpty::new(self.repository.load_commit(self.history.first().id)?);
+        for commit in self.history.iter().skip(1) {
+            commits.push(self.repository.load_commit(commit.id)?);
+        }
+        self.set(vcs::History(commits));
+        Ok(())
+    }
+
+    /// Go through `cache` for the directories, last commits and commits this
+    /// `Browser` computes, see [`Cache`]. The same cache can be given to other
+    /// browsers of the repository.
+    pub fn cache(&mut self, cache: Cache) {
+        self.repository.cache = Some(cache);
+    }
+
     /// Set the current `Browser` history to the `HEAD` commit of the underlying
     /// repository.
     ///
@@ -417,9 +546,16 @@ impl<'a> Browser<'a> {
         let name = tag_name.clone();
         self.set(self.repository.reference(tag_name, |reference| {
             if !ext::is_tag(reference) {
-                Some(Error::NotTag(name))
-            } else {
-                None
+                return Some(Error::NotTag(name));
+            }
+            match reference
+                .peel(git2::ObjectType::Any)
+                .map_err(Error::from)
+                .and_then(|object| ObjectKind::of(&object))
+            {
+                Ok(ObjectKind::Commit) => None,
+                Ok(kind) => Some(Error::TagNotCommit { name, kind }),
+                Err(err) => Some(err),
             }
         })?);
         Ok(())
@@ -473,10 +609,14 @@ impl<'a> Browser<'a> {
 
     /// Set a `Browser`'s [`History`] based on a [revspec](https://git-scm.com/docs/git-rev-parse.html#_specifying_revisions).
     ///
+    /// If the [`Rev`] is a range, e.g. `v0.5.0..master`, the [`History`]
+    /// contains exactly the commits within that range.
+    ///
     /// # Errors
     ///
     /// * [`error::Error::Git`]
     /// * [`error::Error::RevParseFailure`]
+    /// * [`error::Error::EmptyRange`]
     ///
     /// # Examples
     ///
@@ -504,6 +644,30 @@ impl<'a> Browser<'a> {
     /// # Ok(())
     /// # }
     /// ```
+    ///
+    /// ```
+    /// use radicle_surf::vcs::git::{Browser, Branch, Repository, Rev, TagName};
+    /// use std::str::FromStr;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let mut browser = Browser::new(&repo, Branch::local("master"))?;
+    ///
+    /// // The commits released since v0.5.0
+    /// browser.rev(Rev::range(TagName::new("v0.5.0"), Branch::local("master")))?;
+    /// assert_eq!(browser.get().len(), 6);
+    ///
+    /// // Revision expressions are understood as well
+    /// browser.rev(Rev::from_str("master~3..master")?)?;
+    /// assert_eq!(browser.get().len(), 4);
+    ///
+    /// browser.rev(Rev::from_str("v0.6.0~1")?)?;
+    /// assert_eq!(browser.get().first().summary, "Delete unneeded file");
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
     pub fn rev(&mut self, rev: impl Into<Rev>) -> Result<(), Error> {
         let history = self.get_history(rev.into())?;
         self.set(history);
@@ -616,6 +780,48 @@ impl<'a> Browser<'a> {
         self.repository.list_branches(filter)
     }
 
+    /// List the branches within a repository along with their head commit,
+    /// their upstream and how they compare to it, in the order given by
+    /// `sort`.
+    ///
+    /// The upstream of a local branch is read from the `branch.<name>.remote`
+    /// and `branch.<name>.merge` configuration, and remote branches have no
+    /// upstream.
+    ///
+    /// # Errors
+    ///
+    /// * [`error::Error::Git`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::vcs::git::{AheadBehind, Branch, BranchSort, Browser, RefScope, Repository};
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let browser = Browser::new(&repo, Branch::local("master"))?;
+    ///
+    /// let branches = browser.list_branch_info(RefScope::Local, BranchSort::Activity)?;
+    /// let names = branches.iter().map(|info| info.branch.name()).collect::<Vec<_>>();
+    /// assert_eq!(names, vec!["master", "dev"]);
+    ///
+    /// let master = &branches[0];
+    /// assert_eq!(master.head.summary, "Add files with special characters in their filenames (#5)");
+    /// assert_eq!(master.upstream, Some(Branch::remote("master", "origin")));
+    /// assert_eq!(master.ahead_behind, Some(AheadBehind { ahead: 0, behind: 0 }));
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn list_branch_info(
+        &self,
+        scope: RefScope,
+        sort: BranchSort,
+    ) -> Result<Vec<BranchInfo>, Error> {
+        self.repository.list_branch_info(scope, sort)
+    }
+
     /// List the names of the _tags_ that are contained in the underlying
     /// [`Repository`].
     ///
@@ -626,7 +832,7 @@ impl<'a> Browser<'a> {
     /// # Examples
     ///
     /// ```
-    /// use radicle_surf::vcs::git::{Branch, RefScope, Browser, Namespace, Oid, Repository, Tag, TagName, Author, Time};
+    /// use radicle_surf::vcs::git::{Branch, RefScope, Browser, Namespace, ObjectKind, Oid, Repository, Tag, TagName, Author, Time};
     /// use std::convert::TryFrom;
     /// # use std::error::Error;
     ///
@@ -641,32 +847,40 @@ impl<'a> Browser<'a> {
     ///     vec![
     ///         Tag::Light {
     ///             id: Oid::from_str("d3464e33d75c75c99bfb90fa2e9d16efc0b7d0e3")?,
+    ///             kind: ObjectKind::Commit,
     ///             name: TagName::new("v0.1.0"),
     ///             remote: None,
     ///         },
     ///         Tag::Light {
     ///             id: Oid::from_str("2429f097664f9af0c5b7b389ab998b2199ffa977")?,
+    ///             kind: ObjectKind::Commit,
     ///             name: TagName::new("v0.2.0"),
     ///             remote: None,
     ///         },
     ///         Tag::Light {
     ///             id: Oid::from_str("19bec071db6474af89c866a1bd0e4b1ff76e2b97")?,
+    ///             kind: ObjectKind::Commit,
     ///             name: TagName::new("v0.3.0"),
     ///             remote: None,
     ///         },
     ///         Tag::Light {
     ///             id: Oid::from_str("91b69e00cd8e5a07e20942e9e4457d83ce7a3ff1")?,
+    ///             kind: ObjectKind::Commit,
     ///             name: TagName::new("v0.4.0"),
     ///             remote: None,
     ///         },
     ///         Tag::Light {
     ///             id: Oid::from_str("80ded66281a4de2889cc07293a8f10947c6d57fe")?,
+    ///             kind: ObjectKind::Commit,
     ///             name: TagName::new("v0.5.0"),
     ///             remote: None,
     ///         },
     ///         Tag::Annotated {
     ///             id: Oid::from_str("4d1f4af2703074d37cb877f4fdbe36322c8e541d")?,
     ///             target_id: Oid::from_str("d6880352fc7fda8f521ae9b7357668b17bb5bad5")?,
+    ///             target_kind: ObjectKind::Commit,
+    ///             peeled_id: Oid::from_str("d6880352fc7fda8f521ae9b7357668b17bb5bad5")?,
+    ///             peeled_kind: ObjectKind::Commit,
     ///             name: TagName::new("v0.6.0"),
     ///             remote: None,
     ///             tagger: Some(Author {
@@ -686,11 +900,13 @@ impl<'a> Browser<'a> {
     /// assert_eq!(branches, vec![
     ///     Tag::Light {
     ///         id: Oid::from_str("d3464e33d75c75c99bfb90fa2e9d16efc0b7d0e3")?,
+    ///         kind: ObjectKind::Commit,
     ///         name: TagName::new("v0.1.0"),
     ///         remote: None,
     ///     },
     ///     Tag::Light {
     ///         id: Oid::from_str("2429f097664f9af0c5b7b389ab998b2199ffa977")?,
+    ///         kind: ObjectKind::Commit,
     ///         name: TagName::new("v0.2.0"),
     ///         remote: None,
     ///     },
@@ -701,6 +917,7 @@ impl<'a> Browser<'a> {
     /// assert_eq!(branches, vec![
     ///     Tag::Light {
     ///         id: Oid::from_str("d3464e33d75c75c99bfb90fa2e9d16efc0b7d0e3")?,
+    ///         kind: ObjectKind::Commit,
     ///         name: TagName::new("v0.1.0"),
     ///         remote: Some("kickflip".to_string()),
     ///     },
@@ -713,6 +930,87 @@ impl<'a> Browser<'a> {
         self.repository.list_tags(scope)
     }
 
+    /// List the references within the current namespace that are selected by
+    /// `patterns`, sorted by name. References of nested namespaces are only
+    /// listed if an included pattern starts with `refs/namespaces/`.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Git`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::vcs::git::{Branch, Browser, Namespace, RefPatterns, Repository};
+    /// use std::convert::TryFrom;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let browser = Browser::new(&repo, Branch::local("master"))?;
+    ///
+    /// let tag = browser.list_refs(&RefPatterns::new().include("refs/tags/v0.6.0"))?;
+    /// assert_eq!(tag[0].target.to_string(), "4d1f4af2703074d37cb877f4fdbe36322c8e541d");
+    /// assert_eq!(
+    ///     tag[0].peeled.map(|oid| oid.to_string()),
+    ///     Some("d6880352fc7fda8f521ae9b7357668b17bb5bad5".to_string())
+    /// );
+    ///
+    /// let browser = browser.switch_namespace(&Namespace::try_from("golden")?, Branch::local("master"))?;
+    /// let names = browser
+    ///     .list_refs(&RefPatterns::new().exclude("refs/remotes/*"))?
+    ///     .into_iter()
+    ///     .map(|info| info.name)
+    ///     .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(
+    ///     names,
+    ///     vec![
+    ///         "refs/heads/banana",
+    ///         "refs/heads/master",
+    ///         "refs/tags/v0.1.0",
+    ///         "refs/tags/v0.2.0",
+    ///     ]
+    /// );
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn list_refs(&self, patterns: &RefPatterns) -> Result<Vec<RefInfo>, Error> {
+        self.repository.list_refs(patterns)
+    }
+
+    /// List the references under `prefix`, e.g. `refs/pull`, within the
+    /// current namespace. References that are not branches, tags or namespaces
+    /// are listed as [`Ref::Qualified`], and can be browsed using
+    /// [`Browser::rev`].
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Git`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::vcs::git::{Branch, Browser, Ref, Repository};
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let browser = Browser::new(&repo, Branch::local("master"))?;
+    ///
+    /// assert_eq!(
+    ///     browser.list_qualified_refs("refs/heads")?,
+    ///     vec![Ref::from(Branch::local("dev")), Ref::from(Branch::local("master"))]
+    /// );
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn list_qualified_refs(&self, prefix: &str) -> Result<Vec<Ref>, Error> {
+        self.repository.list_qualified_refs(prefix)
+    }
+
     /// List the namespaces within a `Browser`, filtering out ones that do not
     /// parse correctly.
     ///
@@ -749,6 +1047,108 @@ impl<'a> Browser<'a> {
         self.repository.list_namespaces()
     }
 
+    /// Take a [`RefSnapshot`] of the references within the current namespace,
+    /// not including the ones of nested namespaces.
+    ///
+    /// Comparing it with a later snapshot, using
+    /// [`Browser::diff_ref_snapshots`], tells what changed in between.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Git`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::vcs::git::{Branch, Browser, Namespace, Oid, Repository};
+    /// use std::convert::TryFrom;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let browser = Browser::new(&repo, Branch::local("master"))?;
+    /// let browser = browser.switch_namespace(&Namespace::try_from("golden")?, Branch::local("master"))?;
+    ///
+    /// let snapshot = browser.ref_snapshot()?;
+    /// assert_eq!(snapshot.len(), 7);
+    /// assert_eq!(
+    ///     snapshot.get("refs/heads/master"),
+    ///     Some(Oid::from_str("a0dd9122d33dff2a35f564d564db127152c88e02")?)
+    /// );
+    /// assert!(browser.diff_ref_snapshots(&snapshot, &snapshot)?.is_empty());
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn ref_snapshot(&self) -> Result<RefSnapshot, Error> {
+        self.repository.ref_snapshot()
+    }
+
+    /// Compare two [`RefSnapshot`]s, returning the updates of the references
+    /// that differ, sorted by name.
+    ///
+    /// Updates are told apart by looking up the commits in the repository: a
+    /// reference is fast-forwarded if the commit it points to in `new`
+    /// descends from the one in `old`, and force-updated otherwise.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Git`]
+    pub fn diff_ref_snapshots(
+        &self,
+        old: &RefSnapshot,
+        new: &RefSnapshot,
+    ) -> Result<Vec<RefUpdate>, Error> {
+        self.repository.diff_ref_snapshots(old, new)
+    }
+
+    /// Build the tree of the namespaces nested within the current namespace,
+    /// or within the repository if the `Browser` is not in a namespace.
+    ///
+    /// Namespaces that only contain nested namespaces are part of the tree
+    /// too, with no references of their own.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Git`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::vcs::git::{Branch, BranchName, Browser, Namespace, Repository};
+    /// use std::convert::TryFrom;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let browser = Browser::new(&repo, Branch::local("master"))?;
+    ///
+    /// let tree = browser.namespace_tree()?;
+    /// let namespaces = tree.iter().map(|node| node.namespace.to_string()).collect::<Vec<_>>();
+    /// assert_eq!(namespaces, vec!["golden", "me"]);
+    ///
+    /// let golden = &tree[0];
+    /// assert_eq!(golden.refs, 7);
+    /// assert_eq!(golden.heads, vec![BranchName::new("banana"), BranchName::new("master")]);
+    /// assert_eq!(golden.total_refs(), 8);
+    ///
+    /// let silver = &golden.children[0];
+    /// assert_eq!(silver.namespace, Namespace::try_from("golden/silver")?);
+    /// assert_eq!(silver.heads, vec![BranchName::new("master")]);
+    ///
+    /// // Within a namespace, only the nested namespaces are listed.
+    /// let golden = browser.switch_namespace(&Namespace::try_from("golden")?, Branch::local("master"))?;
+    /// let tree = golden.namespace_tree()?;
+    /// assert_eq!(tree.len(), 1);
+    /// assert_eq!(tree[0].namespace, Namespace::try_from("golden/silver")?);
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn namespace_tree(&self) -> Result<Vec<NamespaceTree>, Error> {
+        self.repository.namespace_tree()
+    }
+
     /// Given a [`crate::file_system::Path`] to a file, return the last
     /// [`Commit`] that touched that file or directory.
     ///
@@ -795,12 +1195,8 @@ impl<'a> Browser<'a> {
     /// # }
     /// ```
     pub fn last_commit(&self, path: file_system::Path) -> Result<Option<Commit>, Error> {
-        let file_history = self.repository.file_history(
-            &path,
-            repo::CommitHistory::Last,
-            self.get().first().clone(),
-        )?;
-        Ok(file_history.first().cloned())
+        self.repository
+            .last_commit(&path, self.get().first().clone())
     }
 
     /// Get the commit history for a file _or_ directory.
@@ -868,6 +1264,43 @@ impl<'a> Browser<'a> {
             .file_history(&path, repo::CommitHistory::Full, self.get().first().clone())
     }
 
+    /// Attribute each line of the file at `path`, as of the current
+    /// [`History`], to the commit which last changed it. The identities of the
+    /// commits are resolved according to [`Browser::identities`].
+    ///
+    /// # Errors
+    ///
+    /// * [`error::Error::Git`]
+    /// * [`error::Error::PathNotFound`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::vcs::git::{Branch, Browser, Oid, Repository};
+    /// use radicle_surf::file_system::{Path, unsound};
+    /// use std::str::FromStr;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let mut browser = Browser::new(&repo, Branch::local("master"))?;
+    ///
+    /// // Clamp the Browser to a particular commit
+    /// let commit = Oid::from_str("d6880352fc7fda8f521ae9b7357668b17bb5bad5")?;
+    /// browser.commit(commit)?;
+    ///
+    /// let hunks = browser.blame(Path::with_root(&[unsound::label::new("README.md")]))?;
+    /// assert!(hunks
+    ///     .iter()
+    ///     .all(|hunk| hunk.commit.id == Oid::from_str("d3464e33d75c75c99bfb90fa2e9d16efc0b7d0e3").unwrap()));
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn blame(&self, path: file_system::Path) -> Result<Vec<BlameHunk>, Error> {
+        self.repository.blame(&path, self.get().first().id)
+    }
+
     /// Extract the signature for a commit
     ///
     /// # Arguments
@@ -926,6 +1359,7 @@ impl<'a> Browser<'a> {
     /// # Errors
     ///
     /// * [`error::Error::Git`]
+    /// * [`error::Error::NotASingleRevision`]
     ///
     /// # Examples
     ///
@@ -985,8 +1419,218 @@ impl<'a> Browser<'a> {
         self.repository.revision_branches(&commit.id())
     }
 
+    /// Get the default branch of the current namespace, i.e. the branch `HEAD`
+    /// points to.
+    ///
+    /// # Errors
+    ///
+    /// * [`error::Error::Git`]
+    /// * [`error::Error::NoDefaultBranch`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::vcs::git::{Branch, Browser, Repository};
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let browser = Browser::default(&repo)?;
+    ///
+    /// assert_eq!(browser.default_branch()?, Branch::local("dev"));
+    /// assert_eq!(browser.remote_default_branch("origin")?, Branch::remote("master", "origin"));
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn default_branch(&self) -> Result<Branch, Error> {
+        self.repository.default_branch()
+    }
+
+    /// Get the default branch of `remote`, i.e. the branch
+    /// `refs/remotes/<remote>/HEAD` points to, in the current namespace.
+    ///
+    /// # Errors
+    ///
+    /// * [`error::Error::Git`]
+    /// * [`error::Error::NoDefaultBranch`]
+    pub fn remote_default_branch(&self, remote: &str) -> Result<Branch, Error> {
+        self.repository.remote_default_branch(remote)
+    }
+
+    /// Get the local tags which contain the given `rev`, i.e. the tags from
+    /// which the commit is reachable.
+    ///
+    /// # Errors
+    ///
+    /// * [`error::Error::Git`]
+    /// * [`error::Error::NotASingleRevision`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::vcs::git::{Branch, Browser, Oid, Repository};
+    /// use std::str::FromStr;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let browser = Browser::new(&repo, Branch::local("master"))?;
+    ///
+    /// let tags = browser
+    ///     .revision_tags(Oid::from_str("91b69e00cd8e5a07e20942e9e4457d83ce7a3ff1")?)?
+    ///     .into_iter()
+    ///     .map(|tag| tag.name().to_string())
+    ///     .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(tags, vec!["v0.4.0", "v0.5.0", "v0.6.0"]);
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn revision_tags(&self, rev: impl Into<Rev>) -> Result<Vec<Tag>, Error> {
+        let commit = self.repository.rev_to_commit(&rev.into())?;
+        self.repository.revision_tags(&commit.id())
+    }
+
+    /// Describe the given `rev` in terms of the nearest local tag it can reach,
+    /// similar to `git describe --tags`: the tag with the fewest commits
+    /// between it and `rev`. Annotated and lightweight tags are both
+    /// considered, with annotated tags winning ties.
+    ///
+    /// Returns `None` if no tag can be reached from `rev`.
+    ///
+    /// # Errors
+    ///
+    /// * [`error::Error::Git`]
+    /// * [`error::Error::NotASingleRevision`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::vcs::git::{Branch, Browser, Repository, TagName};
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let browser = Browser::new(&repo, Branch::local("master"))?;
+    ///
+    /// let description = browser.describe(Branch::local("dev"))?.expect("dev is tagged");
+    /// assert_eq!(description.tag, TagName::new("v0.3.0"));
+    /// assert_eq!(description.distance, 4);
+    /// assert_eq!(description.to_string(), "v0.3.0-4-g27acd68");
+    ///
+    /// let description = browser.describe(TagName::new("v0.5.0"))?.expect("v0.5.0 is tagged");
+    /// assert!(description.is_exact());
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn describe(&self, rev: impl Into<Rev>) -> Result<Option<Description>, Error> {
+        let commit = self.repository.rev_to_commit(&rev.into())?;
+        self.repository.describe(commit.id())
+    }
+
+    /// Get the reflog of the given `reference`, within the current namespace,
+    /// starting with the most recent update.
+    ///
+    /// # Errors
+    ///
+    /// * [`error::Error::Git`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::vcs::git::{Branch, Browser, Repository};
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let browser = Browser::new(&repo, Branch::local("master"))?;
+    ///
+    /// let forced = browser
+    ///     .reflog(Branch::local("master"))?
+    ///     .into_iter()
+    ///     .filter(|entry| !entry.fast_forward)
+    ///     .count();
+    ///
+    /// assert_eq!(forced, 0);
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn reflog(&self, reference: impl Into<Ref>) -> Result<Vec<ReflogEntry>, Error> {
+        self.repository.reflog(reference)
+    }
+
+    /// List the notes refs, e.g. `refs/notes/commits`, within the current
+    /// namespace.
+    ///
+    /// # Errors
+    ///
+    /// * [`error::Error::Git`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::vcs::git::{Branch, Browser, Repository};
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let browser = Browser::new(&repo, Branch::local("master"))?;
+    ///
+    /// // git-platinum does not make use of notes
+    /// assert!(browser.list_notes_refs()?.is_empty());
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn list_notes_refs(&self) -> Result<Vec<String>, Error> {
+        self.repository.list_notes_refs()
+    }
+
+    /// Read the [`Note`] attached to the given `rev` under `notes_ref`. If
+    /// `notes_ref` is `None`, the default notes ref is used, i.e.
+    /// `core.notesRef` or [`note::DEFAULT_NOTES_REF`].
+    ///
+    /// Returns `None` if the commit has no note under the notes ref.
+    ///
+    /// # Errors
+    ///
+    /// * [`error::Error::Git`]
+    /// * [`error::Error::NotASingleRevision`]
+    pub fn note(
+        &self,
+        rev: impl Into<Rev>,
+        notes_ref: Option<&str>,
+    ) -> Result<Option<Note>, Error> {
+        let commit = self.repository.rev_to_commit(&rev.into())?;
+        self.repository.note(commit.id(), notes_ref)
+    }
+
+    /// Read all the [`Note`]s attached to the given `rev`, across all the
+    /// notes refs listed by [`Browser::list_notes_refs`].
+    ///
+    /// # Errors
+    ///
+    /// * [`error::Error::Git`]
+    /// * [`error::Error::NotASingleRevision`]
+    pub fn notes(&self, rev: impl Into<Rev>) -> Result<Vec<Note>, Error> {
+        let oid = self.repository.rev_to_commit(&rev.into())?.id();
+        self.repository
+            .list_notes_refs()?
+            .iter()
+            .filter_map(|notes_ref| self.repository.note(oid, Some(notes_ref)).transpose())
+            .collect()
+    }
+
     /// Get the [`Stats`] of the underlying [`Repository`].
     ///
+    /// Contributors are counted from the commit authors as well as any
+    /// co-authors credited in the commit trailers, see [`Commit::co_authors`].
+    /// Their identities are resolved according to [`Browser::identities`].
+    ///
     /// # Errors
     ///
     /// * [`error::Error::Git`]
@@ -1015,12 +1659,13 @@ impl<'a> Browser<'a> {
     pub fn get_stats(&self) -> Result<Stats, Error> {
         let branches = self.list_branches(RefScope::Local)?.len();
         let commits = self.history.len();
+        let mailmap = self.repository.mailmap()?;
 
         let contributors = self
             .history
             .iter()
-            .cloned()
-            .map(|commit| (commit.author.name, commit.author.email))
+            .flat_map(|commit| commit.authors(mailmap))
+            .map(|author| (author.name, author.email))
             .collect::<BTreeSet<_>>();
 
         Ok(Stats {
@@ -1030,6 +1675,61 @@ impl<'a> Browser<'a> {
         })
     }
 
+    /// Get the [`DetailedStats`] of the history starting at the given
+    /// revision, breaking down the activity per contributor and per week.
+    ///
+    /// Like [`Browser::get_stats`], co-authors are credited for the commits
+    /// they co-authored.
+    ///
+    /// # Errors
+    ///
+    /// * [`error::Error::Git`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::vcs::git::{Branch, Browser, Repository};
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let browser = Browser::new(&repo, Branch::local("master"))?;
+    ///
+    /// let stats = browser.get_detailed_stats(Branch::local("master"))?;
+    ///
+    /// assert_eq!(stats.commits, 15);
+    /// assert_eq!(stats.branches, 2);
+    /// assert_eq!(stats.tags, 6);
+    /// assert_eq!(stats.contributors.len(), 4);
+    ///
+    /// // Every commit is accounted for in the weekly activity.
+    /// let weekly: usize = stats.activity.iter().map(|week| week.commits).sum();
+    /// assert_eq!(weekly, stats.commits);
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get_detailed_stats(&self, rev: impl Into<Rev>) -> Result<DetailedStats, Error> {
+        let history = self.get_history(rev.into())?;
+        let mailmap = self.repository.mailmap()?;
+        let mut tally = stats::Tally::default();
+
+        for commit in history.iter() {
+            let authors = commit.authors(mailmap);
+            let (additions, deletions) = self.repository.line_stats(commit)?;
+            tally.record(&authors, commit.author.time.seconds(), additions, deletions);
+        }
+
+        let (contributors, activity) = tally.finish();
+        Ok(DetailedStats {
+            commits: history.len(),
+            branches: self.list_branches(RefScope::Local)?.len(),
+            tags: self.list_tags(RefScope::Local)?.len(),
+            contributors,
+            activity,
+        })
+    }
+
     /// Do a pre-order TreeWalk of the given commit. This turns a Tree
     /// into a HashMap of Paths and a list of Files. We can then turn that
     /// into a Directory.
@@ -1094,6 +1794,95 @@ impl<'a> Browser<'a> {
         }
     }
 
+    /// Find the best common ancestor of the commits `one` and `two` point to,
+    /// if it exists.
+    ///
+    /// The references are resolved by their full name, regardless of the
+    /// namespace of the `Browser`, so that references of different namespaces
+    /// can be compared. Use [`Ref::namespaced`] to refer to the references of
+    /// a namespace.
+    ///
+    /// # Errors
+    ///
+    /// * [`error::Error::Git`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use radicle_surf::vcs::git::{AheadBehind, Branch, Browser, Namespace, Oid, Ref, Repository};
+    /// use std::convert::TryFrom;
+    /// # use std::error::Error;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let repo = Repository::new("./data/git-platinum")?;
+    /// let browser = Browser::new(&repo, Branch::local("master"))?;
+    ///
+    /// let golden = Ref::from(Branch::local("master")).namespaced(Namespace::try_from("golden")?);
+    /// let silver =
+    ///     Ref::from(Branch::local("master")).namespaced(Namespace::try_from("golden/silver")?);
+    ///
+    /// assert_eq!(
+    ///     browser.merge_base_refs(&golden, &silver)?,
+    ///     Some(Oid::from_str("1820cb07c1a890016ca5578aa652fd4d4c38967e")?)
+    /// );
+    /// assert_eq!(
+    ///     browser.ahead_behind_refs(&golden, &silver)?,
+    ///     AheadBehind { ahead: 8, behind: 1 }
+    /// );
+    /// assert_eq!(browser.commits_between(&silver, &golden)?.len(), 8);
+    /// assert!(!browser.diff_refs(&silver, &golden)?.modified.is_empty());
+    ///
+    /// // The namespace of the `Browser` is left untouched.
+    /// assert_eq!(browser.which_namespace()?, None);
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn merge_base_refs(&self, one: &Ref, two: &Ref) -> Result<Option<Oid>, Error> {
+        self.merge_base(
+            self.repository.peel_absolute(one)?,
+            self.repository.peel_absolute(two)?,
+        )
+    }
+
+    /// Get the [`Diff`] between the commits `from` and `to` point to.
+    ///
+    /// As for [`Browser::merge_base_refs`], the references are resolved
+    /// regardless of the namespace of the `Browser`.
+    ///
+    /// # Errors
+    ///
+    /// * [`error::Error::Git`]
+    pub fn diff_refs(&self, from: &Ref, to: &Ref) -> Result<Diff, Error> {
+        self.repository.diff_refs(from, to)
+    }
+
+    /// Count the commits reachable from `head` but not from `base`, and the
+    /// other way around.
+    ///
+    /// As for [`Browser::merge_base_refs`], the references are resolved
+    /// regardless of the namespace of the `Browser`.
+    ///
+    /// # Errors
+    ///
+    /// * [`error::Error::Git`]
+    pub fn ahead_behind_refs(&self, head: &Ref, base: &Ref) -> Result<AheadBehind, Error> {
+        self.repository.ahead_behind_refs(head, base)
+    }
+
+    /// Get the commits reachable from `head` but not from `base`, i.e. the
+    /// commits of `base..head`, most recent first.
+    ///
+    /// As for [`Browser::merge_base_refs`], the references are resolved
+    /// regardless of the namespace of the `Browser`.
+    ///
+    /// # Errors
+    ///
+    /// * [`error::Error::Git`]
+    pub fn commits_between(&self, base: &Ref, head: &Ref) -> Result<Vec<Commit>, Error> {
+        self.repository.commits_between(base, head)
+    }
+
     fn update_file_map(
         path: file_system::Path,
         name: file_system::Label,
@@ -1281,7 +2070,7 @@ mod tests {
 
     #[cfg(test)]
     mod rev {
-        use super::{Branch, Browser, Error, Oid, Repository, TagName};
+        use super::{Branch, Browser, Error, Oid, Repository, Rev, TagName};
 
         // **FIXME**: This seems to break occasionally on
         // buildkite. For some reason the commit
@@ -1397,6 +2186,24 @@ mod tests {
 
             Ok(())
         }
+
+        #[test]
+        fn ranges_are_not_single_revisions() -> Result<(), Error> {
+            let repo = Repository::new("./data/git-platinum")?;
+            let browser = Browser::new(&repo, Branch::local("master"))?;
+
+            for range in &[
+                Rev::range(TagName::new("v0.5.0"), Branch::local("master")),
+                Rev::symmetric(Branch::local("dev"), Branch::local("master")),
+            ] {
+                match browser.describe(range.clone()) {
+                    Err(Error::NotASingleRevision { rev }) => assert_eq!(rev, range.to_string()),
+                    result => panic!("expected NotASingleRevision, got {:?}", result),
+                }
+            }
+
+            Ok(())
+        }
     }
 
     #[cfg(test)]
@@ -0,0 +1,31 @@
+// This file is part of radicle-surf
+// <https://github.com/radicle-dev/radicle-surf>
+//
+// Copyright (C) 2019-2020 The Radicle Team <dev@radicle.xyz>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 or
+// later as published by the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::vcs::git::Commit;
+
+/// A run of consecutive lines of a file which were last changed by the same
+/// commit, see [`crate::vcs::git::Browser::blame`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlameHunk {
+    /// The commit which last changed the lines. Its identities are resolved
+    /// according to [`crate::vcs::git::Browser::identities`].
+    pub commit: Commit,
+    /// The number of the first line of the hunk, starting at 1.
+    pub start_line: usize,
+    /// The number of lines in the hunk.
+    pub lines: usize,
+}
@@ -15,7 +15,7 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
-use crate::vcs::git::{self, error::Error, ext, reference::Ref};
+use crate::vcs::git::{self, error::Error, ext, reference::Ref, Commit};
 use std::{cmp::Ordering, convert::TryFrom, fmt, str};
 
 /// The branch type we want to filter on.
@@ -191,3 +191,124 @@ impl<'repo> TryFrom<git2::Reference<'repo>> for Branch {
         }
     }
 }
+
+/// How many commits a branch and its upstream have that the other does not.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AheadBehind {
+    /// The number of commits on the branch that are not on its upstream.
+    pub ahead: usize,
+    /// The number of commits on the upstream that are not on the branch.
+    pub behind: usize,
+}
+
+/// The order in which [`crate::vcs::git::Browser::list_branch_info`] returns
+/// branches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BranchSort {
+    /// By name, as for [`Branch`].
+    Name,
+    /// By the committer time of the head commit, most recent first. Branches
+    /// with the same time are sorted by name.
+    Activity,
+}
+
+/// A [`Branch`] along with its head commit and upstream tracking information.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BranchInfo {
+    /// The branch itself.
+    pub branch: Branch,
+    /// The commit the branch points to.
+    pub head: Commit,
+    /// The upstream branch, as configured by `branch.<name>.remote` and
+    /// `branch.<name>.merge` and mapped by the fetch refspecs of the remote.
+    /// Only local branches have an upstream.
+    pub upstream: Option<Branch>,
+    /// How the branch compares to its upstream, if the upstream exists in the
+    /// repository.
+    pub ahead_behind: Option<AheadBehind>,
+}
+
+impl BranchInfo {
+    /// The time of the last activity on the branch, i.e. the committer time of
+    /// its head commit.
+    pub fn last_activity(&self) -> git2::Time {
+        self.head.committer.time
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::vcs::git::{fixture::TempRepository, *};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn tracks_upstream() -> Result<(), Error> {
+        let repo = TempRepository::new()?;
+        let base = repo.empty_commit("Base", 0, &[])?;
+        let local = repo.empty_commit("Local", 20, &[base])?;
+        let first = repo.empty_commit("First", 10, &[base])?;
+        let second = repo.empty_commit("Second", 10, &[first])?;
+
+        repo.0.reference("refs/heads/feature", local, true, "")?;
+        repo.0.reference("refs/heads/master", second, true, "")?;
+        repo.0.reference("refs/heads/stale", base, true, "")?;
+        repo.0
+            .reference("refs/remotes/origin/master", second, true, "")?;
+
+        let mut config = repo.0.config()?;
+        config.set_str("branch.feature.remote", ".")?;
+        config.set_str("branch.feature.merge", "refs/heads/master")?;
+        config.set_str("branch.master.remote", "origin")?;
+        config.set_str("branch.master.merge", "refs/heads/master")?;
+        config.set_str("branch.stale.remote", "fork")?;
+        config.set_str("branch.stale.merge", "refs/heads/main")?;
+        config.set_str("remote.origin.url", "https://example.com/origin.git")?;
+        config.set_str("remote.origin.fetch", "+refs/heads/*:refs/remotes/origin/*")?;
+        config.set_str("remote.fork.url", "https://example.com/fork.git")?;
+        config.set_str(
+            "remote.fork.fetch",
+            "+refs/heads/main:refs/remotes/fork/trunk",
+        )?;
+
+        let browser = Browser::new(&repo, Branch::local("master"))?;
+
+        let branches = browser.list_branch_info(RefScope::Local, BranchSort::Name)?;
+        let names = branches
+            .iter()
+            .map(|info| info.branch.name())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["feature", "master", "stale"]);
+
+        assert_eq!(branches[0].upstream, Some(Branch::local("master")));
+        assert_eq!(
+            branches[0].ahead_behind,
+            Some(AheadBehind {
+                ahead: 1,
+                behind: 2
+            })
+        );
+        assert_eq!(
+            branches[1].upstream,
+            Some(Branch::remote("master", "origin"))
+        );
+        assert_eq!(
+            branches[1].ahead_behind,
+            Some(AheadBehind {
+                ahead: 0,
+                behind: 0
+            })
+        );
+        assert_eq!(branches[2].upstream, Some(Branch::remote("trunk", "fork")));
+        assert_eq!(branches[2].ahead_behind, None);
+
+        let branches = browser.list_branch_info(RefScope::All, BranchSort::Activity)?;
+        let names = branches
+            .iter()
+            .map(|info| info.branch.name())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["feature", "master", "origin/master", "stale"]);
+        assert_eq!(branches[2].upstream, None);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,515 @@
+// This file is part of radicle-surf
+// <https://github.com/radicle-dev/radicle-surf>
+//
+// Copyright (C) 2019-2020 The Radicle Team <dev@radicle.xyz>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 or
+// later as published by the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! A cache of results derived from immutable objects, which can be shared by
+//! the browsers of a repository.
+//!
+//! Since the objects are identified by their content, the cached results
+//! never go stale. Commits converted with a mailmap are also keyed by the blob
+//! id of the mailmap, so that changing it converts them again.
+
+use crate::{
+    file_system::{Directory, Path},
+    vcs::git::{error::Error, Commit},
+};
+use git2::{ObjectType, Oid};
+use std::{
+    collections::{BTreeMap, HashMap},
+    hash::Hash,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+/// The bounds on the size of a [`Cache`]. The least recently used results
+/// are evicted first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CacheLimits {
+    /// The total size, in bytes, of the files of the cached directories. See
+    /// [`Directory::size`].
+    pub tree_bytes: usize,
+    /// The number of cached last commits of a path.
+    pub last_commits: usize,
+    /// The number of cached commits.
+    pub commits: usize,
+}
+
+impl Default for CacheLimits {
+    fn default() -> Self {
+        Self {
+            tree_bytes: 64 * 1024 * 1024,
+            last_commits: 4096,
+            commits: 16384,
+        }
+    }
+}
+
+/// The metrics of one kind of cached results.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheCounts {
+    /// The number of lookups which found a cached result.
+    pub hits: u64,
+    /// The number of lookups which had to compute the result.
+    pub misses: u64,
+    /// The number of results evicted to stay within the limits.
+    pub evictions: u64,
+    /// The number of cached results.
+    pub len: usize,
+    /// The size of the cached results, in the unit of their limit.
+    pub size: usize,
+}
+
+/// The metrics of a [`Cache`], as returned by [`Cache::stats`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// The directories of trees, see [`crate::vcs::git::Browser::get_directory`].
+    pub trees: CacheCounts,
+    /// The last commits of paths, see
+    /// [`crate::vcs::git::Browser::last_commit`].
+    pub last_commits: CacheCounts,
+    /// The commits of histories.
+    pub commits: CacheCounts,
+}
+
+/// A cache of directories, last commits and commits, keyed by the `Oid`s
+/// they are derived from.
+///
+/// Cloning a `Cache` gives another handle to the same cache, which can be
+/// given to any number of browsers, on any thread, with
+/// [`crate::vcs::git::Browser::cache`] or
+/// [`crate::vcs::git::BrowserHandle::cache`]. Since the results are keyed by
+/// content alone, browsers of different repositories can share a cache too.
+///
+/// # Examples
+///
+/// ```
+/// use radicle_surf::{
+///     file_system::unsound,
+///     vcs::git::{Branch, Browser, Cache, Repository},
+/// };
+/// # use std::error::Error;
+///
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// let repo = Repository::new("./data/git-platinum")?;
+/// let cache = Cache::new();
+///
+/// for _ in 0..2 {
+///     let mut browser = Browser::new(&repo, Branch::local("master"))?;
+///     browser.cache(cache.clone());
+///     browser.get_directory()?;
+///     browser.last_commit(unsound::path::new("src/memory.rs"))?;
+/// }
+///
+/// let stats = cache.stats();
+/// assert_eq!((stats.trees.hits, stats.trees.misses), (1, 1));
+/// assert_eq!((stats.last_commits.hits, stats.last_commits.misses), (1, 1));
+/// #
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Cache {
+    inner: Arc<Mutex<Caches>>,
+}
+
+#[derive(Debug, Default)]
+struct Caches {
+    trees: Lru<Oid, Directory>,
+    last_commits: Lru<(Oid, Path), Option<Oid>>,
+    commits: Lru<(Oid, IdentityKey), Commit>,
+}
+
+/// The identities a commit was converted with: either as is, or with the
+/// mailmap whose contents hash to the blob id.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) enum IdentityKey {
+    Raw,
+    Mailmap(Oid),
+}
+
+impl IdentityKey {
+    /// The key of the mailmap with `contents`. An empty mailmap leaves the
+    /// identities as is.
+    pub(crate) fn mailmap(contents: &[u8]) -> Result<Self, Error> {
+        if contents.is_empty() {
+            Ok(Self::Raw)
+        } else {
+            Ok(Self::Mailmap(Oid::hash_object(ObjectType::Blob, contents)?))
+        }
+    }
+}
+
+impl Cache {
+    /// Create an empty cache with the [`CacheLimits::default`] limits.
+    pub fn new() -> Self {
+        Self::with_limits(CacheLimits::default())
+    }
+
+    /// Create an empty cache with the given `limits`.
+    pub fn with_limits(limits: CacheLimits) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(Caches {
+                trees: Lru::new(limits.tree_bytes),
+                last_commits: Lru::new(limits.last_commits),
+                commits: Lru::new(limits.commits),
+            })),
+        }
+    }
+
+    /// The metrics of the cache.
+    pub fn stats(&self) -> CacheStats {
+        let caches = self.lock();
+        CacheStats {
+            trees: caches.trees.counts(),
+            last_commits: caches.last_commits.counts(),
+            commits: caches.commits.counts(),
+        }
+    }
+
+    /// Remove all cached results. The metrics are kept.
+    pub fn clear(&self) {
+        let mut caches = self.lock();
+        caches.trees.clear();
+        caches.last_commits.clear();
+        caches.commits.clear();
+    }
+
+    pub(crate) fn tree<F>(&self, tree: Oid, f: F) -> Result<Directory, Error>
+    where
+        F: FnOnce() -> Result<Directory, Error>,
+    {
+        self.get_or_insert_with(
+            |caches| &mut caches.trees,
+            tree,
+            f,
+            |directory| directory.size().max(1),
+        )
+    }
+
+    pub(crate) fn last_commit<F>(&self, head: Oid, path: &Path, f: F) -> Result<Option<Oid>, Error>
+    where
+        F: FnOnce() -> Result<Option<Oid>, Error>,
+    {
+        self.get_or_insert_with(
+            |caches| &mut caches.last_commits,
+            (head, path.clone()),
+            f,
+            |_| 1,
+        )
+    }
+
+    pub(crate) fn commit<F>(&self, key: (Oid, IdentityKey), f: F) -> Result<Commit, Error>
+    where
+        F: FnOnce() -> Result<Commit, Error>,
+    {
+        self.get_or_insert_with(|caches| &mut caches.commits, key, f, |_| 1)
+    }
+
+    /// Look `key` up, or compute its value with `f` and cache it. The lock is
+    /// not held while computing, so that other browsers are not blocked.
+    fn get_or_insert_with<K, V, S, F, W>(
+        &self,
+        select: S,
+        key: K,
+        f: F,
+        weight: W,
+    ) -> Result<V, Error>
+    where
+        K: Clone + Eq + Hash,
+        V: Clone,
+        S: Fn(&mut Caches) -> &mut Lru<K, V>,
+        F: FnOnce() -> Result<V, Error>,
+        W: FnOnce(&V) -> usize,
+    {
+        if let Some(value) = select(&mut self.lock()).get(&key) {
+            return Ok(value);
+        }
+        let value = f()?;
+        let weight = weight(&value);
+        select(&mut self.lock()).insert(key, value.clone(), weight);
+        Ok(value)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Caches> {
+        // The caches are valid even if a thread panicked while holding the
+        // lock.
+        self.inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// A map keeping the most recently used entries whose total weight is within
+/// `limit`.
+#[derive(Debug)]
+struct Lru<K, V> {
+    limit: usize,
+    size: usize,
+    tick: u64,
+    entries: HashMap<K, Entry<V>>,
+    order: BTreeMap<u64, K>,
+    counts: CacheCounts,
+}
+
+#[derive(Debug)]
+struct Entry<V> {
+    value: V,
+    weight: usize,
+    tick: u64,
+}
+
+impl<K, V> Default for Lru<K, V> {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl<K, V> Lru<K, V> {
+    fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            size: 0,
+            tick: 0,
+            entries: HashMap::new(),
+            order: BTreeMap::new(),
+            counts: CacheCounts::default(),
+        }
+    }
+
+    fn counts(&self) -> CacheCounts {
+        CacheCounts {
+            len: self.entries.len(),
+            size: self.size,
+            ..self.counts
+        }
+    }
+
+    fn clear(&mut self) {
+        self.size = 0;
+        self.entries.clear();
+        self.order.clear();
+    }
+}
+
+impl<K, V> Lru<K, V>
+where
+    K: Clone + Eq + Hash,
+    V: Clone,
+{
+    fn get(&mut self, key: &K) -> Option<V> {
+        self.tick += 1;
+        match self.entries.get_mut(key) {
+            Some(entry) => {
+                self.counts.hits += 1;
+                self.order.remove(&entry.tick);
+                entry.tick = self.tick;
+                self.order.insert(self.tick, key.clone());
+                Some(entry.value.clone())
+            },
+            None => {
+                self.counts.misses += 1;
+                None
+            },
+        }
+    }
+
+    fn insert(&mut self, key: K, value: V, weight: usize) {
+        if weight > self.limit {
+            return;
+        }
+        self.tick += 1;
+        if let Some(old) = self.entries.remove(&key) {
+            self.order.remove(&old.tick);
+            self.size -= old.weight;
+        }
+        self.order.insert(self.tick, key.clone());
+        self.entries.insert(
+            key,
+            Entry {
+                value,
+                weight,
+                tick: self.tick,
+            },
+        );
+        self.size += weight;
+
+        while self.size > self.limit {
+            let oldest = match self.order.keys().next() {
+                Some(tick) => *tick,
+                None => break,
+            };
+            if let Some(key) = self.order.remove(&oldest) {
+                if let Some(entry) = self.entries.remove(&key) {
+                    self.size -= entry.weight;
+                    self.counts.evictions += 1;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        file_system::{unsound, Directory, File},
+        vcs::git::*,
+    };
+    use pretty_assertions::assert_eq;
+    use std::{sync::Arc, thread};
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let mut lru = Lru::new(3);
+        lru.insert("a", 1, 1);
+        lru.insert("b", 2, 1);
+        lru.insert("c", 3, 1);
+        assert_eq!(lru.get(&"a"), Some(1));
+
+        lru.insert("d", 4, 2);
+        assert_eq!(lru.get(&"b"), None);
+        assert_eq!(lru.get(&"c"), None);
+        assert_eq!(lru.get(&"a"), Some(1));
+        assert_eq!(lru.get(&"d"), Some(4));
+
+        // Entries larger than the limit are not cached at all.
+        lru.insert("e", 5, 4);
+        assert_eq!(lru.get(&"e"), None);
+
+        assert_eq!(
+            lru.counts(),
+            CacheCounts {
+                hits: 3,
+                misses: 3,
+                evictions: 2,
+                len: 2,
+                size: 3,
+            }
+        );
+    }
+
+    #[test]
+    fn shares_results_between_browsers() -> Result<(), Error> {
+        let cache = Cache::new();
+        let pool = Arc::new(RepositoryPool::new("./data/git-platinum")?);
+        let handle = BrowserHandle::new(pool)
+            .rev(Branch::local("master"))
+            .cache(cache.clone());
+
+        let threads = (0..2)
+            .map(|_| {
+                let handle = handle.clone();
+                thread::spawn(move || {
+                    handle.with_browser(|browser| {
+                        let directory = browser.get_directory()?;
+                        let last = browser.last_commit(unsound::path::new("src/memory.rs"))?;
+                        Ok::<_, Error>((directory, last))
+                    })
+                })
+            })
+            .collect::<Vec<_>>();
+        let results = threads
+            .into_iter()
+            .map(|thread| thread.join().expect("the thread panicked"))
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(results[0], results[1]);
+
+        let repo = Repository::new("./data/git-platinum")?;
+        let browser = Browser::new(&repo, Branch::local("master"))?;
+        assert_eq!(browser.get_directory()?, results[0].0);
+        assert_eq!(
+            browser.last_commit(unsound::path::new("src/memory.rs"))?,
+            results[0].1
+        );
+
+        let stats = cache.stats();
+        assert_eq!(stats.trees.hits + stats.trees.misses, 2);
+        assert_eq!(stats.trees.len, 1);
+        assert_eq!(stats.last_commits.len, 1);
+        // Both histories went through the cache, which keeps each commit
+        // once.
+        assert!(stats.commits.hits + stats.commits.misses >= 30);
+        assert_eq!(stats.commits.len, 15);
+
+        Ok(())
+    }
+
+    #[test]
+    fn stays_within_limits() -> Result<(), Error> {
+        let cache = Cache::with_limits(CacheLimits {
+            tree_bytes: 0,
+            last_commits: 1,
+            commits: 4,
+        });
+        let repo = Repository::new("./data/git-platinum")?;
+        let mut browser = Browser::new(&repo, Branch::local("master"))?;
+        browser.cache(cache.clone());
+        browser.get_directory()?;
+        browser.last_commit(unsound::path::new("src/memory.rs"))?;
+        browser.last_commit(unsound::path::new("README.md"))?;
+        browser.rev(Branch::local("master"))?;
+
+        let stats = cache.stats();
+        assert_eq!((stats.trees.len, stats.trees.size), (0, 0));
+        assert_eq!(stats.last_commits.len, 1);
+        assert_eq!(stats.last_commits.evictions, 1);
+        assert_eq!(stats.commits.len, 4);
+
+        cache.clear();
+        assert_eq!(cache.stats().commits.len, 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn keys_commits_by_mailmap() -> Result<(), Error> {
+        let repo = EphemeralRepository::new()?;
+        let master = Branch::local("master");
+        let mut root = Directory::root();
+        let mailmap = |email: &str| {
+            File::new(format!("Radicle <{}> <{}>\n", email, ephemeral::AUTHOR.1).as_bytes())
+        };
+        root.insert_file(unsound::path::new(".mailmap"), mailmap("first@example.com"));
+        repo.commit(master.clone(), &root, "First")?;
+
+        let cache = Cache::new();
+        let emails = |repo: &EphemeralRepository| -> Result<Vec<String>, Error> {
+            let mut browser = Browser::new(repo, master.clone())?;
+            browser.cache(cache.clone());
+            browser.identities(Identities::MailmapAt(master.clone().into()))?;
+            Ok(browser
+                .get()
+                .iter()
+                .map(|commit| commit.author.email.clone())
+                .collect())
+        };
+        assert_eq!(emails(&repo)?, vec!["first@example.com"]);
+
+        // The branch now points to another mailmap, so the first commit is
+        // converted again.
+        root.insert_file(
+            unsound::path::new(".mailmap"),
+            mailmap("second@example.com"),
+        );
+        repo.commit(master.clone(), &root, "Second")?;
+        assert_eq!(
+            emails(&repo)?,
+            vec!["second@example.com", "second@example.com"]
+        );
+        assert_eq!(cache.stats().commits.len, 3);
+
+        Ok(())
+    }
+}
@@ -16,9 +16,16 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use crate::vcs::git::error::Error;
+use encoding_rs::{Encoding, UTF_8};
 use git2::Oid;
 use std::{convert::TryFrom, str};
 
+#[cfg(feature = "serialize")]
+use serde::Serialize;
+
+/// The trailer key used by git hosting platforms to credit additional authors.
+const CO_AUTHORED_BY: &str = "Co-authored-by";
+
 /// `Author` is the static information of a [`git2::Signature`].
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Author {
@@ -46,16 +53,186 @@ impl std::fmt::Debug for Author {
     }
 }
 
-impl<'repo> TryFrom<git2::Signature<'repo>> for Author {
-    type Error = str::Utf8Error;
+impl Author {
+    /// Decode the `signature` using the given `encoding`, also returning
+    /// whether any of it had to be replaced with `U+FFFD`.
+    fn decode(signature: &git2::Signature, encoding: &'static Encoding) -> (Self, bool) {
+        let (name, lossy_name) = decode(signature.name_bytes(), encoding);
+        let (email, lossy_email) = decode(signature.email_bytes(), encoding);
+        let author = Author {
+            name,
+            email,
+            time: signature.when(),
+        };
+        (author, lossy_name || lossy_email)
+    }
+
+    /// Resolve the identity of this `Author` through the `mailmap`, keeping
+    /// the `Author` as is if it has no entry.
+    pub(crate) fn resolve(self, mailmap: &git2::Mailmap) -> Self {
+        let resolved = git2::Signature::new(&self.name, &self.email, &self.time)
+            .and_then(|signature| mailmap.resolve_signature(&signature));
+        match resolved {
+            Ok(signature) => Author {
+                time: self.time,
+                ..Author::from(signature)
+            },
+            Err(_) => self,
+        }
+    }
+}
+
+/// Signatures are decoded as UTF-8, replacing any invalid sequences with
+/// `U+FFFD`.
+impl<'repo> From<git2::Signature<'repo>> for Author {
+    fn from(signature: git2::Signature) -> Self {
+        Author::decode(&signature, UTF_8).0
+    }
+}
+
+/// Decode `bytes` with the given `encoding`, falling back to replacing any
+/// malformed sequences with `U+FFFD`. The returned `bool` is `true` if such a
+/// replacement happened.
+fn decode(bytes: &[u8], encoding: &'static Encoding) -> (String, bool) {
+    let (decoded, lossy) = encoding.decode_without_bom_handling(bytes);
+    (decoded.into_owned(), lossy)
+}
+
+/// The undecoded name and email of a [`git2::Signature`].
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct RawSignature {
+    /// The raw bytes of the name.
+    pub name: Vec<u8>,
+    /// The raw bytes of the email.
+    pub email: Vec<u8>,
+}
+
+impl<'a, 'repo> From<&'a git2::Signature<'repo>> for RawSignature {
+    fn from(signature: &'a git2::Signature<'repo>) -> Self {
+        RawSignature {
+            name: signature.name_bytes().to_vec(),
+            email: signature.email_bytes().to_vec(),
+        }
+    }
+}
+
+/// The undecoded metadata of a [`Commit`], kept around when it could not be
+/// decoded without loss.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct RawCommit {
+    /// The raw author signature.
+    pub author: RawSignature,
+    /// The raw committer signature.
+    pub committer: RawSignature,
+    /// The raw bytes of the commit message.
+    pub message: Vec<u8>,
+}
+
+/// A trailer found at the end of a commit message, e.g.
+/// `Signed-off-by: Alice <alice@example.com>`.
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Trailer {
+    /// The key of the trailer, e.g. `Signed-off-by`.
+    pub key: String,
+    /// The value of the trailer, with continuation lines joined by a space.
+    pub value: String,
+}
+
+impl Trailer {
+    /// Parse a single `Key: value` line into a `Trailer`.
+    ///
+    /// The key may only consist of alphanumeric characters and `-`, and the
+    /// value must not be empty.
+    pub fn parse(line: &str) -> Option<Self> {
+        let (key, value) = line.split_once(':')?;
+        let value = value.trim();
+        let is_key = !key.is_empty() && key.chars().all(|c| c.is_alphanumeric() || c == '-');
+        if !is_key || value.is_empty() {
+            return None;
+        }
+
+        Some(Trailer {
+            key: key.to_string(),
+            value: value.to_string(),
+        })
+    }
+
+    /// Check if the key of this `Trailer` matches `key`, ignoring ASCII case.
+    pub fn is(&self, key: &str) -> bool {
+        self.key.eq_ignore_ascii_case(key)
+    }
+
+    /// Interpret the value of this `Trailer` as an identity of the form
+    /// `Name <email>`, returning the name and the email.
+    pub fn identity(&self) -> Option<(&str, &str)> {
+        let (name, rest) = self.value.rsplit_once('<')?;
+        let email = rest.strip_suffix('>')?;
+        Some((name.trim(), email.trim()))
+    }
+}
+
+/// Split a commit message into the text preceding its trailers and the
+/// trailers themselves.
+///
+/// Trailers are taken from the last paragraph of the message, as long as that
+/// paragraph is not the only one and every line in it is either a trailer or
+/// the indented continuation of one. Otherwise the message is returned as is,
+/// with no trailers.
+///
+/// # Examples
+///
+/// ```
+/// use radicle_surf::vcs::git::{commit::split_trailers, Trailer};
+///
+/// let message = "Fix the flux capacitor\n\nIt was broken.\n\nSigned-off-by: Alice <alice@example.com>\n";
+/// let (body, trailers) = split_trailers(message);
+///
+/// assert_eq!(body, "Fix the flux capacitor\n\nIt was broken.");
+/// assert_eq!(trailers, vec![Trailer {
+///     key: "Signed-off-by".to_string(),
+///     value: "Alice <alice@example.com>".to_string(),
+/// }]);
+/// ```
+pub fn split_trailers(message: &str) -> (&str, Vec<Trailer>) {
+    let trimmed = message.trim_end();
+
+    // Walk the lines backwards to find the blank line that starts the last
+    // paragraph.
+    let mut end = trimmed.len();
+    let mut paragraph = None;
+    for (newline, _) in trimmed.rmatch_indices('\n') {
+        if trimmed[newline + 1..end].trim().is_empty() {
+            paragraph = Some((newline, end + 1));
+            break;
+        }
+        end = newline;
+    }
 
-    fn try_from(signature: git2::Signature) -> Result<Self, Self::Error> {
-        let name = str::from_utf8(signature.name_bytes())?.into();
-        let email = str::from_utf8(signature.email_bytes())?.into();
-        let time = signature.when();
+    let (body_end, start) = match paragraph {
+        Some(bounds) => bounds,
+        None => return (message, vec![]),
+    };
 
-        Ok(Author { name, email, time })
+    let mut trailers: Vec<Trailer> = vec![];
+    for line in trimmed[start..].lines() {
+        if line.starts_with(char::is_whitespace) {
+            match trailers.last_mut() {
+                Some(trailer) => {
+                    trailer.value.push(' ');
+                    trailer.value.push_str(line.trim());
+                },
+                None => return (message, vec![]),
+            }
+        } else {
+            match Trailer::parse(line) {
+                Some(trailer) => trailers.push(trailer),
+                None => return (message, vec![]),
+            }
+        }
     }
+
+    (trimmed[..body_end].trim_end(), trailers)
 }
 
 /// `Commit` is the static information of a [`git2::Commit`]. To get back the
@@ -75,20 +252,124 @@ pub struct Commit {
     pub summary: String,
     /// The parents of this commit.
     pub parents: Vec<Oid>,
+    /// The trailers at the end of the commit message, see [`split_trailers`].
+    pub trailers: Vec<Trailer>,
+    /// The value of the commit's `encoding` header, if it was present.
+    pub encoding: Option<String>,
+    /// The raw commit metadata, if any of the author, committer or message
+    /// could not be decoded and had invalid sequences replaced with `U+FFFD`.
+    pub raw: Option<RawCommit>,
+}
+
+impl Commit {
+    /// The commit description, i.e. the message without its summary and
+    /// trailers.
+    pub fn description(&self) -> &str {
+        let (body, _) = split_trailers(&self.message);
+        body.strip_prefix(&self.summary).unwrap_or(body).trim()
+    }
+
+    /// The additional authors credited through `Co-authored-by` trailers.
+    ///
+    /// Since trailers do not carry a timestamp, each co-author is given the
+    /// time of the commit's [`Commit::author`].
+    pub fn co_authors(&self) -> Vec<Author> {
+        self.trailers
+            .iter()
+            .filter(|trailer| trailer.is(CO_AUTHORED_BY))
+            .filter_map(Trailer::identity)
+            .map(|(name, email)| Author {
+                name: name.to_string(),
+                email: email.to_string(),
+                time: self.author.time,
+            })
+            .collect()
+    }
+
+    /// The author of the commit followed by its co-authors, whose identities
+    /// are resolved through the `mailmap`, if provided. Co-authors naming an
+    /// identity already listed, such as the author's, are skipped.
+    pub(crate) fn authors(&self, mailmap: Option<&git2::Mailmap>) -> Vec<Author> {
+        let mut authors = vec![self.author.clone()];
+        for co_author in self.co_authors() {
+            let co_author = match mailmap {
+                Some(mailmap) => co_author.resolve(mailmap),
+                None => co_author,
+            };
+            let listed = authors
+                .iter()
+                .any(|author| author.name == co_author.name && author.email == co_author.email);
+            if !listed {
+                authors.push(co_author);
+            }
+        }
+        authors
+    }
 }
 
+/// The metadata of the commit is decoded using its `encoding` header, or as
+/// UTF-8 if the header is missing or names an unknown encoding. Decoding never
+/// fails: malformed sequences are replaced with `U+FFFD` and the original bytes
+/// are kept in [`Commit::raw`].
 impl<'repo> TryFrom<git2::Commit<'repo>> for Commit {
     type Error = Error;
 
     fn try_from(commit: git2::Commit) -> Result<Self, Self::Error> {
+        Commit::with_mailmap(commit, None)
+    }
+}
+
+impl Commit {
+    /// Convert a [`git2::Commit`] like [`Commit::try_from`], resolving the
+    /// author and committer identities through the `mailmap`, if provided.
+    pub(crate) fn with_mailmap(
+        commit: git2::Commit,
+        mailmap: Option<&git2::Mailmap>,
+    ) -> Result<Self, Error> {
         let id = commit.id();
-        let author = Author::try_from(commit.author())?;
-        let committer = Author::try_from(commit.committer())?;
+        let encoding = commit.message_encoding().map(String::from);
+        let decoder = encoding
+            .as_ref()
+            .and_then(|label| Encoding::for_label(label.as_bytes()))
+            .unwrap_or(UTF_8);
+
+        let (author_signature, committer_signature) = match mailmap {
+            None => (commit.author(), commit.committer()),
+            Some(mailmap) => (
+                commit.author_with_mailmap(mailmap)?,
+                commit.committer_with_mailmap(mailmap)?,
+            ),
+        };
+        // Identities coming from the mailmap are always UTF-8.
+        let author_decoder = if is_same_identity(&author_signature, &commit.author()) {
+            decoder
+        } else {
+            UTF_8
+        };
+        let committer_decoder = if is_same_identity(&committer_signature, &commit.committer()) {
+            decoder
+        } else {
+            UTF_8
+        };
+
+        let (author, lossy_author) = Author::decode(&author_signature, author_decoder);
+        let (committer, lossy_committer) = Author::decode(&committer_signature, committer_decoder);
         let message_raw = commit.message_bytes();
-        let message = str::from_utf8(message_raw)?.into();
-        let summary_raw = commit.summary_bytes().ok_or(Error::MissingSummary)?;
-        let summary = str::from_utf8(summary_raw)?.into();
+        let (message, lossy_message) = decode(message_raw, decoder);
+        // Commits with an empty message have no summary.
+        let (summary, _) = decode(commit.summary_bytes().unwrap_or_default(), decoder);
         let parents = commit.parent_ids().collect();
+        let (_, trailers) = split_trailers(&message);
+
+        let raw = if lossy_author || lossy_committer || lossy_message {
+            Some(RawCommit {
+                author: RawSignature::from(&author_signature),
+                committer: RawSignature::from(&committer_signature),
+                message: message_raw.to_vec(),
+            })
+        } else {
+            None
+        };
 
         Ok(Commit {
             id,
@@ -97,6 +378,163 @@ impl<'repo> TryFrom<git2::Commit<'repo>> for Commit {
             message,
             summary,
             parents,
+            trailers,
+            encoding,
+            raw,
         })
     }
 }
+
+fn is_same_identity(left: &git2::Signature, right: &git2::Signature) -> bool {
+    left.name_bytes() == right.name_bytes() && left.email_bytes() == right.email_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vcs::git::fixture::TempRepository;
+
+    fn trailer(key: &str, value: &str) -> Trailer {
+        Trailer {
+            key: key.to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_split_trailers() {
+        let message = "Summary\n\nSome description.\n\nSigned-off-by: Alice <alice@example.com>\nCo-authored-by: Bob <bob@example.com>\nFixes: a really long\n  explanation\n";
+        assert_eq!(
+            split_trailers(message),
+            (
+                "Summary\n\nSome description.",
+                vec![
+                    trailer("Signed-off-by", "Alice <alice@example.com>"),
+                    trailer("Co-authored-by", "Bob <bob@example.com>"),
+                    trailer("Fixes", "a really long explanation"),
+                ]
+            )
+        );
+
+        assert_eq!(
+            split_trailers("Summary\n\nReviewed-by: Carol <carol@example.com>"),
+            (
+                "Summary",
+                vec![trailer("Reviewed-by", "Carol <carol@example.com>")]
+            )
+        );
+    }
+
+    #[test]
+    fn test_split_no_trailers() {
+        // The summary on its own is never a trailer block.
+        assert_eq!(
+            split_trailers("Fixes: nothing\n"),
+            ("Fixes: nothing\n", vec![])
+        );
+
+        // Every line of the last paragraph needs to be a trailer.
+        let message = "Summary\n\nSigned-off-by: Alice <alice@example.com>\nnot a trailer\n";
+        assert_eq!(split_trailers(message), (message, vec![]));
+
+        let message = "Summary\n\nA description: with a colon\n";
+        assert_eq!(split_trailers(message), (message, vec![]));
+    }
+
+    #[test]
+    fn test_trailer_identity() {
+        assert_eq!(
+            trailer("Co-authored-by", "Bob Builder <bob@example.com>").identity(),
+            Some(("Bob Builder", "bob@example.com"))
+        );
+        assert_eq!(trailer("Fixes", "#42").identity(), None);
+    }
+
+    fn write_commit(raw: &[u8]) -> Result<Commit, Error> {
+        let repo = TempRepository::new()?;
+        let oid = repo.0.odb()?.write(git2::ObjectType::Commit, raw)?;
+        let commit = Commit::try_from(repo.0.find_commit(oid)?);
+        commit
+    }
+
+    #[test]
+    fn test_encoding_header() -> Result<(), Error> {
+        let commit = write_commit(
+            b"tree 4b825dc642cb6eb9a060e54bf8d69288fbee4904\n\
+              author Andr\xe9 <andre@example.com> 1620740737 +0200\n\
+              committer Andr\xe9 <andre@example.com> 1620740737 +0200\n\
+              encoding ISO-8859-1\n\
+              \n\
+              Caf\xe9\n",
+        )?;
+
+        assert_eq!(commit.author.name, "Andr\u{e9}");
+        assert_eq!(commit.summary, "Caf\u{e9}");
+        assert_eq!(commit.encoding, Some("ISO-8859-1".to_string()));
+        assert_eq!(commit.raw, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_lossy_fallback() -> Result<(), Error> {
+        let commit = write_commit(
+            b"tree 4b825dc642cb6eb9a060e54bf8d69288fbee4904\n\
+              author Andr\xe9 <andre@example.com> 1620740737 +0200\n\
+              committer Andr\xc3\xa9 <andre@example.com> 1620740737 +0200\n\
+              \n\
+              Caf\xe9\n",
+        )?;
+
+        assert_eq!(commit.author.name, "Andr\u{fffd}");
+        assert_eq!(commit.committer.name, "Andr\u{e9}");
+        assert_eq!(commit.message, "Caf\u{fffd}\n");
+        assert_eq!(commit.encoding, None);
+
+        let raw = commit.raw.expect("missing raw commit metadata");
+        assert_eq!(raw.author.name, b"Andr\xe9");
+        assert_eq!(raw.message, b"Caf\xe9\n");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_empty_message() -> Result<(), Error> {
+        let commit = write_commit(
+            b"tree 4b825dc642cb6eb9a060e54bf8d69288fbee4904\n\
+              author Alice <alice@example.com> 1620740737 +0200\n\
+              committer Alice <alice@example.com> 1620740737 +0200\n\
+              \n",
+        )?;
+
+        assert_eq!(commit.summary, "");
+        assert_eq!(commit.message, "");
+        assert_eq!(commit.trailers, vec![]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_authors_skip_listed_co_authors() -> Result<(), Error> {
+        let commit = write_commit(
+            b"tree 4b825dc642cb6eb9a060e54bf8d69288fbee4904\n\
+              author Alice <alice@example.com> 1620740737 +0200\n\
+              committer Alice <alice@example.com> 1620740737 +0200\n\
+              \n\
+              Pair\n\
+              \n\
+              Co-authored-by: Alice <alice@example.com>\n\
+              Co-authored-by: Bob <bob@example.com>\n\
+              Co-authored-by: Bob <bob@example.com>\n",
+        )?;
+
+        let authors = commit
+            .authors(None)
+            .into_iter()
+            .map(|author| author.name)
+            .collect::<Vec<_>>();
+        assert_eq!(authors, vec!["Alice", "Bob"]);
+
+        Ok(())
+    }
+}
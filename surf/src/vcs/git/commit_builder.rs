@@ -0,0 +1,335 @@
+// This file is part of radicle-surf
+// <https://github.com/radicle-dev/radicle-surf>
+//
+// Copyright (C) 2019-2020 The Radicle Team <dev@radicle.xyz>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 or
+// later as published by the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+    file_system::Directory,
+    vcs::git::{error::Error, Author, Ref, RepositoryRef},
+};
+use git2::{ErrorCode, Oid};
+
+/// Creates a commit of the contents of a [`Directory`], without a working
+/// copy.
+///
+/// # Examples
+///
+/// ```
+/// use radicle_surf::{
+///     file_system::{unsound, Directory, File},
+///     vcs::git::{ephemeral::EphemeralRepository, Author, Branch, Browser, CommitBuilder, Time},
+/// };
+/// # use std::error::Error;
+///
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// let repo = EphemeralRepository::new()?;
+/// let master = Branch::local("master");
+/// let mut root = Directory::root();
+/// root.insert_file(unsound::path::new("README.md"), File::new(b"# Hello"));
+/// let first = repo.commit(master.clone(), &root, "Add README")?;
+///
+/// root.insert_file(unsound::path::new("README.md"), File::new(b"# Hello, World"));
+/// let bot = Author {
+///     name: "bot".to_string(),
+///     email: "bot@example.com".to_string(),
+///     time: Time::new(1_600_000_000, 0),
+/// };
+/// let second = CommitBuilder::new(&root, "Greet the world")
+///     .parent(first)
+///     .author(bot.clone())
+///     .update_ref(master.clone())
+///     .commit(&repo.as_ref())?;
+///
+/// let browser = Browser::new(&repo, master)?;
+/// let head = browser.get().first().clone();
+/// assert_eq!(head.id, second);
+/// assert_eq!(head.author, bot);
+/// assert_eq!(head.parents, vec![first]);
+/// #
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct CommitBuilder<'a> {
+    directory: &'a Directory,
+    message: String,
+    parents: Vec<Oid>,
+    author: Option<Author>,
+    committer: Option<Author>,
+    update_ref: Option<Ref>,
+}
+
+impl<'a> CommitBuilder<'a> {
+    /// Commit `directory` as the whole tree of the commit, with `message`.
+    pub fn new(directory: &'a Directory, message: impl Into<String>) -> Self {
+        Self {
+            directory,
+            message: message.into(),
+            parents: Vec::new(),
+            author: None,
+            committer: None,
+            update_ref: None,
+        }
+    }
+
+    /// Add `parent` to the parents of the commit. The first parent is the one
+    /// `git log --first-parent` follows.
+    pub fn parent(mut self, parent: Oid) -> Self {
+        self.parents.push(parent);
+        self
+    }
+
+    /// Set the author of the commit. By default, it is the signature
+    /// configured with `user.name` and `user.email`, at the current time.
+    pub fn author(mut self, author: Author) -> Self {
+        self.author = Some(author);
+        self
+    }
+
+    /// Set the committer of the commit. By default, it is the author.
+    pub fn committer(mut self, committer: Author) -> Self {
+        self.committer = Some(committer);
+        self
+    }
+
+    /// Point `reference` at the new commit, within the current namespace of
+    /// the repository.
+    ///
+    /// The reference must point to the first parent of the commit, or not
+    /// exist if the commit has no parents, so that concurrent updates are not
+    /// overwritten.
+    pub fn update_ref(mut self, reference: impl Into<Ref>) -> Self {
+        self.update_ref = Some(reference.into());
+        self
+    }
+
+    /// Write the directory, the commit and the reference update to
+    /// `repository`, and return the `Oid` of the commit.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::RefUpdateConflict`], if the reference to update moved
+    /// * [`Error::Git`], e.g. if there is no default author configured
+    pub fn commit(&self, repository: &RepositoryRef) -> Result<Oid, Error> {
+        let repo = repository.repo_ref;
+        // Check the reference first, so that nothing is written on a conflict.
+        let found = match &self.update_ref {
+            Some(reference) => {
+                let reference = match repository.which_namespace()? {
+                    None => reference.clone(),
+                    Some(namespace) => reference.clone().namespaced(namespace),
+                };
+                let found = self.check(repository, &reference)?;
+                Some((reference, found))
+            },
+            None => None,
+        };
+
+        let tree = repo.find_tree(repository.write_directory(self.directory)?)?;
+        let parents = self
+            .parents
+            .iter()
+            .map(|parent| repo.find_commit(*parent))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let author = match &self.author {
+            Some(author) => git2::Signature::new(&author.name, &author.email, &author.time)?,
+            None => repo.signature()?,
+        };
+        let committer = match &self.committer {
+            Some(committer) => {
+                git2::Signature::new(&committer.name, &committer.email, &committer.time)?
+            },
+            None => author.clone(),
+        };
+
+        let oid = repo.commit(
+            None,
+            &author,
+            &committer,
+            &self.message,
+            &tree,
+            &parents.iter().collect::<Vec<_>>(),
+        )?;
+
+        if let Some((reference, found)) = found {
+            self.update(repository, &reference, found, oid)?;
+        }
+
+        Ok(oid)
+    }
+
+    /// Check that `reference` points to the first parent, and return the
+    /// commit it points to.
+    fn check(&self, repository: &RepositoryRef, reference: &Ref) -> Result<Option<Oid>, Error> {
+        let expected = self.parents.first().copied();
+        let found = current(repository, reference)?;
+        if found != expected {
+            return Err(Error::RefUpdateConflict {
+                name: reference.to_string(),
+                expected,
+                found,
+            });
+        }
+        Ok(found)
+    }
+
+    fn update(
+        &self,
+        repository: &RepositoryRef,
+        reference: &Ref,
+        found: Option<Oid>,
+        oid: Oid,
+    ) -> Result<(), Error> {
+        let repo = repository.repo_ref;
+        let name = reference.to_string();
+        let summary = self.message.lines().next().unwrap_or_default();
+        let log_message = format!("commit: {}", summary);
+        // The reference may still move between reading and writing it, which
+        // libgit2 reports when writing.
+        let written = match found {
+            Some(current) => repo.reference_matching(&name, oid, true, current, &log_message),
+            None => repo.reference(&name, oid, false, &log_message),
+        };
+        match written {
+            Ok(_) => Ok(()),
+            Err(err) if err.code() == ErrorCode::Modified || err.code() == ErrorCode::Exists => {
+                Err(Error::RefUpdateConflict {
+                    expected: self.parents.first().copied(),
+                    found: current(repository, reference)?,
+                    name,
+                })
+            },
+            Err(err) => Err(err.into()),
+        }
+    }
+}
+
+/// The commit `reference` points to, if it exists.
+fn current(repository: &RepositoryRef, reference: &Ref) -> Result<Option<Oid>, Error> {
+    match reference.find_ref(repository) {
+        Ok(reference) => Ok(Some(reference.peel_to_commit()?.id())),
+        Err(err) if err.code() == ErrorCode::NotFound => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        file_system::{unsound, Directory, File},
+        vcs::git::*,
+    };
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn refuses_to_overwrite_moved_refs() -> Result<(), Error> {
+        let repo = EphemeralRepository::new()?;
+        let mut root = Directory::root();
+        root.insert_file(unsound::path::new("a.txt"), File::new(b"a"));
+        let first = repo.commit(Branch::local("master"), &root, "First")?;
+        root.insert_file(unsound::path::new("b.txt"), File::new(b"b"));
+        let second = repo.commit(Branch::local("master"), &root, "Second")?;
+        let mut moved = root.clone();
+        moved.insert_file(unsound::path::new("c.txt"), File::new(b"stale"));
+
+        let author = Author {
+            name: "bot".to_string(),
+            email: "bot@example.com".to_string(),
+            time: Time::new(100, 0),
+        };
+        let stale = CommitBuilder::new(&moved, "Stale")
+            .parent(first)
+            .author(author.clone())
+            .update_ref(Branch::local("master"));
+        assert_eq!(
+            stale.commit(&repo.as_ref()).err(),
+            Some(Error::RefUpdateConflict {
+                name: "refs/heads/master".to_string(),
+                expected: Some(first),
+                found: Some(second),
+            })
+        );
+        // Nothing was written for the rejected commit.
+        let stale_blob = Oid::hash_object(git2::ObjectType::Blob, b"stale")?;
+        assert!(repo.0.find_blob(stale_blob).is_err());
+
+        let root_commit = CommitBuilder::new(&root, "Root")
+            .author(author.clone())
+            .update_ref(Branch::local("master"));
+        assert_eq!(
+            root_commit.commit(&repo.as_ref()).err(),
+            Some(Error::RefUpdateConflict {
+                name: "refs/heads/master".to_string(),
+                expected: None,
+                found: Some(second),
+            })
+        );
+
+        // Without a reference to update, any commit can be written.
+        let oid = CommitBuilder::new(&root, "Detached")
+            .parent(first)
+            .author(author)
+            .commit(&repo.as_ref())?;
+        let commit = repo.0.find_commit(oid)?;
+        assert_eq!(commit.tree_id(), repo.0.find_commit(second)?.tree_id());
+        assert_eq!(commit.committer().name(), Some("bot"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn updates_refs_within_the_namespace() -> Result<(), Error> {
+        let repo = EphemeralRepository::new()?;
+        let mut root = Directory::root();
+        root.insert_file(unsound::path::new("a.txt"), File::new(b"a"));
+        repo.commit(Branch::local("master"), &root, "Outside")?;
+
+        let repo_ref = repo.as_ref();
+        repo_ref.switch_namespace("golden")?;
+        let first = CommitBuilder::new(&root, "First")
+            .update_ref(Branch::local("master"))
+            .author(Author {
+                name: "bot".to_string(),
+                email: "bot@example.com".to_string(),
+                time: Time::new(100, 0),
+            })
+            .commit(&repo_ref)?;
+        assert_eq!(
+            repo.0
+                .find_reference("refs/namespaces/golden/refs/heads/master")?
+                .target(),
+            Some(first)
+        );
+
+        let second = CommitBuilder::new(&root, "Second")
+            .parent(first)
+            .update_ref(Branch::local("master"))
+            .author(Author {
+                name: "bot".to_string(),
+                email: "bot@example.com".to_string(),
+                time: Time::new(200, 0),
+            })
+            .commit(&repo_ref)?;
+        assert_eq!(
+            repo.0
+                .find_reference("refs/namespaces/golden/refs/heads/master")?
+                .target(),
+            Some(second)
+        );
+
+        Ok(())
+    }
+}
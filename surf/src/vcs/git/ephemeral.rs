@@ -0,0 +1,235 @@
+// This file is part of radicle-surf
+// <https://github.com/radicle-dev/radicle-surf>
+//
+// Copyright (C) 2019-2020 The Radicle Team <dev@radicle.xyz>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 or
+// later as published by the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Throwaway repositories built from [`Directory`] values, for tests which
+//! should not depend on fixtures on disk.
+//!
+//! libgit2 can keep objects in memory, but not references, so an
+//! [`EphemeralRepository`] is a bare repository in a fresh temporary
+//! directory, which is removed when it is dropped. A directory left behind by
+//! a process which aborted is never reused, and is cleaned up along with the
+//! rest of the temporary directory.
+
+use crate::{
+    file_system::Directory,
+    vcs::git::{error::Error, Author, CommitBuilder, Ref, Repository, RepositoryRef},
+};
+use git2::Oid;
+use std::{cell::Cell, ops::Deref, path::Path};
+use tempfile::TempDir;
+
+/// The name and email of the author and committer of the commits made by an
+/// [`EphemeralRepository`].
+pub const AUTHOR: (&str, &str) = ("Radicle", "dev@radicle.xyz");
+
+/// A bare repository in a fresh temporary directory, which is removed when
+/// the `EphemeralRepository` is dropped.
+///
+/// Commits are made by [`AUTHOR`], one second after each other starting at
+/// the epoch, so that their `Oid`s are the same on every run.
+///
+/// # Examples
+///
+/// ```
+/// use radicle_surf::{
+///     file_system::{unsound, Directory, File},
+///     vcs::git::{ephemeral::EphemeralRepository, Branch, Browser},
+/// };
+/// # use std::error::Error;
+///
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// let repo = EphemeralRepository::new()?;
+///
+/// let mut root = Directory::root();
+/// root.insert_file(unsound::path::new("README.md"), File::new(b"# Hello"));
+/// repo.commit(Branch::local("master"), &root, "Add README")?;
+///
+/// root.insert_file(unsound::path::new("src/lib.rs"), File::new(b"pub mod hello;"));
+/// repo.commit(Branch::local("master"), &root, "Add lib")?;
+/// repo.set_head(Branch::local("master"))?;
+///
+/// let browser = Browser::default(&repo)?;
+/// assert_eq!(browser.get().len(), 2);
+/// assert_eq!(browser.get_directory()?, root);
+/// #
+/// # Ok(())
+/// # }
+/// ```
+pub struct EphemeralRepository {
+    // Declared before `dir`, so that the repository is closed before its
+    // directory is removed.
+    repo: Repository,
+    dir: TempDir,
+    commits: Cell<i64>,
+}
+
+impl EphemeralRepository {
+    /// Create an empty repository.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Git`], also if the temporary directory cannot be created
+    pub fn new() -> Result<Self, Error> {
+        let dir = tempfile::Builder::new()
+            .prefix("surf-ephemeral-")
+            .tempdir()
+            .map_err(|err| {
+                git2::Error::from_str(&format!("failed to create a temporary directory: {}", err))
+            })?;
+        let repo = git2::Repository::init_bare(dir.path())?;
+        Ok(Self {
+            repo: Repository(repo),
+            dir,
+            commits: Cell::new(0),
+        })
+    }
+
+    /// The path of the repository.
+    pub fn path(&self) -> &Path {
+        self.dir.path()
+    }
+
+    /// Commit the contents of `directory` to `reference`, on top of the commit
+    /// it points to if it exists, and return the `Oid` of the new commit.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Git`]
+    pub fn commit(
+        &self,
+        reference: impl Into<Ref>,
+        directory: &Directory,
+        message: &str,
+    ) -> Result<Oid, Error> {
+        let reference = reference.into();
+        let parent = match reference.find_ref(&self.repo.as_ref()) {
+            Ok(current) => Some(current.peel_to_commit()?.id()),
+            Err(err) if err.code() == git2::ErrorCode::NotFound => None,
+            Err(err) => return Err(err.into()),
+        };
+
+        let time = self.commits.get();
+        self.commits.set(time + 1);
+        let author = Author {
+            name: AUTHOR.0.to_string(),
+            email: AUTHOR.1.to_string(),
+            time: git2::Time::new(time, 0),
+        };
+
+        let mut builder = CommitBuilder::new(directory, message)
+            .author(author)
+            .update_ref(reference);
+        if let Some(parent) = parent {
+            builder = builder.parent(parent);
+        }
+        builder.commit(&self.repo.as_ref())
+    }
+
+    /// Point `reference` at `oid`, creating it if it does not exist.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Git`]
+    pub fn reference(&self, reference: impl Into<Ref>, oid: Oid) -> Result<(), Error> {
+        self.repo
+            .0
+            .reference(&reference.into().to_string(), oid, true, "")?;
+        Ok(())
+    }
+
+    /// Point `HEAD` at `reference`, which is the default branch of the
+    /// repository.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Git`]
+    pub fn set_head(&self, reference: impl Into<Ref>) -> Result<(), Error> {
+        self.repo.0.set_head(&reference.into().to_string())?;
+        Ok(())
+    }
+}
+
+impl Deref for EphemeralRepository {
+    type Target = Repository;
+
+    fn deref(&self) -> &Self::Target {
+        &self.repo
+    }
+}
+
+impl<'a> From<&'a EphemeralRepository> for RepositoryRef<'a> {
+    fn from(repo: &'a EphemeralRepository) -> Self {
+        repo.repo.as_ref()
+    }
+}
+
+impl std::fmt::Debug for EphemeralRepository {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "EphemeralRepository({})", self.path().display())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        file_system::{unsound, Directory, File},
+        vcs::git::*,
+    };
+    use pretty_assertions::assert_eq;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn builds_reproducible_repositories() -> Result<(), Error> {
+        let mut root = Directory::root();
+        root.insert_file(unsound::path::new("text/hello.txt"), File::new(b"hello"));
+        let master = Branch::local("master");
+        let golden = Ref::LocalBranch {
+            name: BranchName::new("master"),
+        }
+        .namespaced(Namespace::try_from("golden")?);
+
+        let first = EphemeralRepository::new()?;
+        let second = EphemeralRepository::new()?;
+        assert_ne!(first.path(), second.path());
+        let oid = first.commit(master.clone(), &root, "Add text")?;
+        assert_eq!(second.commit(golden, &root, "Add text")?, oid);
+
+        first.set_head(master)?;
+        {
+            let browser = Browser::default(&first)?;
+            assert_eq!(
+                browser
+                    .get_directory()?
+                    .find_file(unsound::path::new("text/hello.txt")),
+                Some(File::new(b"hello"))
+            );
+        }
+
+        let browser = Browser::new_with_namespace(
+            &second,
+            &Namespace::try_from("golden")?,
+            Branch::local("master"),
+        )?;
+        assert_eq!(browser.get().first().id, oid);
+
+        let path = first.path().to_path_buf();
+        drop(first);
+        assert!(!path.exists());
+
+        Ok(())
+    }
+}
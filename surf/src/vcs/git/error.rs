@@ -21,9 +21,9 @@
 use crate::{
     diff,
     file_system,
-    vcs::git::{BranchName, Namespace, TagName},
+    vcs::git::{BranchName, Namespace, ObjectKind, TagName},
 };
-use std::str;
+use std::{path::PathBuf, str};
 use thiserror::Error;
 
 /// Enumeration of errors that can occur in operations from [`crate::vcs::git`].
@@ -43,6 +43,16 @@ pub enum Error {
     /// or that a branch or commit was provided by accident.
     #[error("provided tag name does not exist: {0}")]
     NotTag(TagName),
+    /// The user tried to browse a tag, but the tag points to a tree or a blob
+    /// rather than to a commit, so it has no history.
+    #[error("tag '{name}' points to a {kind} and not to a commit")]
+    TagNotCommit {
+        /// The name of the tag.
+        name: TagName,
+        /// The type of the object the tag points to, after following any
+        /// nested tags.
+        kind: ObjectKind,
+    },
     /// A `revspec` was provided that could not be parsed into a branch, tag, or
     /// commit object.
     #[error("provided revspec '{rev}' could not be parsed into a git object")]
@@ -59,6 +69,57 @@ pub enum Error {
         /// The provided revspec that failed to parse.
         rev: String,
     },
+    /// The symbolic reference expected to point to the default branch, e.g.
+    /// `HEAD`, is detached or does not point to a branch.
+    #[error("could not determine the default branch, '{0}' does not point to a branch")]
+    NoDefaultBranch(String),
+    /// A revision range was provided that contains no commits.
+    #[error("provided revision range '{rev}' contains no commits")]
+    EmptyRange {
+        /// The provided revision range.
+        rev: String,
+    },
+    /// A revision range was provided where a single commit is needed.
+    #[error("provided revision '{rev}' is a range, not a single revision")]
+    NotASingleRevision {
+        /// The provided revision range.
+        rev: String,
+    },
+    /// No repository was found at the given path, or above it when
+    /// discovering.
+    #[error("could not find a repository at '{}'", .0.display())]
+    RepositoryNotFound(PathBuf),
+    /// The repository has no linked worktree of the given name.
+    #[error("provided worktree name does not exist: {0}")]
+    WorktreeNotFound(String),
+    /// The linked worktree exists, but its working copy was moved or deleted.
+    #[error("worktree '{name}' is not valid: {reason}")]
+    InvalidWorktree {
+        /// The name of the worktree.
+        name: String,
+        /// Why the worktree is not valid.
+        reason: String,
+    },
+    /// `GIT_COMMON_DIR` is set, but the repository found keeps its objects and
+    /// references elsewhere.
+    #[error("repository '{}' does not have the common directory '{}'", .git_dir.display(), .common_dir.display())]
+    CommonDirMismatch {
+        /// The `.git` directory of the repository found.
+        git_dir: PathBuf,
+        /// The common directory expected from the environment.
+        common_dir: PathBuf,
+    },
+    /// A reference was not updated to a new commit because it no longer
+    /// pointed to the commit's first parent.
+    #[error("reference '{name}' was expected to point to {expected:?}, but points to {found:?}")]
+    RefUpdateConflict {
+        /// The name of the reference.
+        name: String,
+        /// The first parent of the new commit, if any.
+        expected: Option<git2::Oid>,
+        /// The commit the reference points to, if it exists.
+        found: Option<git2::Oid>,
+    },
     /// When parsing a namespace we may come across one that was an empty
     /// string.
     #[error("tried parsing the namespace but it was empty")]
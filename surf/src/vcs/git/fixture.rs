@@ -0,0 +1,88 @@
+// This file is part of radicle-surf
+// <https://github.com/radicle-dev/radicle-surf>
+//
+// Copyright (C) 2019-2020 The Radicle Team <dev@radicle.xyz>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 or
+// later as published by the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Bare repositories in temporary directories, for tests which need to write
+//! objects and references.
+
+use crate::vcs::git::{error::Error, Oid, Repository, RepositoryRef, Time};
+use std::ops::Deref;
+use tempfile::TempDir;
+
+/// A bare repository which is removed, together with its directory, when it
+/// is dropped.
+pub(crate) struct TempRepository {
+    // Fields are dropped in order, so the repository is closed before its
+    // directory is removed.
+    repo: Repository,
+    _dir: TempDir,
+}
+
+impl TempRepository {
+    /// Initialise an empty bare repository in a fresh temporary directory.
+    pub(crate) fn new() -> Result<Self, Error> {
+        let dir = temp_dir()?;
+        let repo = git2::Repository::init_bare(dir.path())?;
+        Ok(Self {
+            repo: Repository::from(repo),
+            _dir: dir,
+        })
+    }
+
+    /// Commit the empty tree on top of `parents` at `time`, without updating
+    /// any reference.
+    pub(crate) fn empty_commit(
+        &self,
+        message: &str,
+        time: i64,
+        parents: &[Oid],
+    ) -> Result<Oid, Error> {
+        let repo = &self.repo.0;
+        let signature = git2::Signature::new("Alice", "alice@example.com", &Time::new(time, 0))?;
+        let tree = repo.find_tree(repo.treebuilder(None)?.write()?)?;
+        let parents = parents
+            .iter()
+            .map(|parent| repo.find_commit(*parent))
+            .collect::<Result<Vec<_>, _>>()?;
+        let parents = parents.iter().collect::<Vec<_>>();
+        Ok(repo.commit(None, &signature, &signature, message, &tree, &parents)?)
+    }
+}
+
+impl Deref for TempRepository {
+    type Target = Repository;
+
+    fn deref(&self) -> &Self::Target {
+        &self.repo
+    }
+}
+
+impl<'a> From<&'a TempRepository> for RepositoryRef<'a> {
+    fn from(repo: &'a TempRepository) -> Self {
+        repo.repo.as_ref()
+    }
+}
+
+/// A fresh temporary directory, which is removed when it is dropped.
+pub(crate) fn temp_dir() -> Result<TempDir, Error> {
+    let dir = tempfile::Builder::new()
+        .prefix("surf-test-")
+        .tempdir()
+        .map_err(|err| {
+            git2::Error::from_str(&format!("failed to create a temporary directory: {}", err))
+        })?;
+    Ok(dir)
+}
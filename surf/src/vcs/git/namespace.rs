@@ -15,7 +15,7 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
-use crate::vcs::git::error::Error;
+use crate::vcs::git::{error::Error, BranchName};
 pub use git2::Oid;
 use nonempty::NonEmpty;
 use std::{convert::TryFrom, fmt, str};
@@ -30,6 +30,42 @@ pub struct Namespace {
     pub(super) values: NonEmpty<String>,
 }
 
+/// A namespace along with the namespaces nested within it, as found by
+/// [`super::Browser::namespace_tree`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NamespaceTree {
+    /// The full namespace, e.g. `golden/silver`, which can be given to
+    /// [`super::Browser::switch_namespace`].
+    pub namespace: Namespace,
+    /// The number of references directly within the namespace, i.e. not
+    /// counting the ones of nested namespaces.
+    pub refs: usize,
+    /// The branches under `refs/heads` within the namespace, sorted by name.
+    pub heads: Vec<BranchName>,
+    /// The branch the namespace's `HEAD` points to, if it has one.
+    pub head: Option<BranchName>,
+    /// The namespaces nested directly within this one, sorted by name.
+    pub children: Vec<NamespaceTree>,
+}
+
+impl NamespaceTree {
+    pub(super) fn new(namespace: Namespace) -> Self {
+        Self {
+            namespace,
+            refs: 0,
+            heads: vec![],
+            head: None,
+            children: vec![],
+        }
+    }
+
+    /// The number of references within the namespace, including the ones of
+    /// nested namespaces.
+    pub fn total_refs(&self) -> usize {
+        self.refs + self.children.iter().map(Self::total_refs).sum::<usize>()
+    }
+}
+
 impl fmt::Display for Namespace {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let values: Vec<_> = self.values.clone().into();
@@ -80,3 +116,54 @@ impl TryFrom<git2::Reference<'_>> for Namespace {
             .ok_or(Error::EmptyNamespace)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::vcs::git::{fixture::TempRepository, *};
+    use pretty_assertions::assert_eq;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn includes_intermediate_namespaces() -> Result<(), Error> {
+        let repo = TempRepository::new()?;
+        let commit = repo.empty_commit("First", 0, &[])?;
+        repo.0.reference("refs/heads/master", commit, true, "")?;
+        repo.0.reference(
+            "refs/namespaces/a/refs/namespaces/b/refs/heads/main",
+            commit,
+            true,
+            "",
+        )?;
+        repo.0.reference_symbolic(
+            "refs/namespaces/a/refs/namespaces/b/HEAD",
+            "refs/namespaces/a/refs/namespaces/b/refs/heads/main",
+            true,
+            "",
+        )?;
+        repo.0
+            .reference("refs/namespaces/c/refs/tags/v1", commit, true, "")?;
+
+        let browser = Browser::new(&repo, Branch::local("master"))?;
+
+        let tree = browser.namespace_tree()?;
+        assert_eq!(tree.len(), 2);
+
+        let a = &tree[0];
+        assert_eq!(a.namespace, Namespace::try_from("a")?);
+        assert_eq!((a.refs, a.head.clone()), (0, None));
+        assert_eq!(a.total_refs(), 1);
+
+        let b = &a.children[0];
+        assert_eq!(b.namespace, Namespace::try_from("a/b")?);
+        assert_eq!(b.refs, 1);
+        assert_eq!(b.heads, vec![BranchName::new("main")]);
+        assert_eq!(b.head, Some(BranchName::new("main")));
+        assert!(b.children.is_empty());
+
+        let c = &tree[1];
+        assert_eq!(c.namespace, Namespace::try_from("c")?);
+        assert_eq!((c.refs, c.heads.len()), (1, 0));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,104 @@
+// This file is part of radicle-surf
+// <https://github.com/radicle-dev/radicle-surf>
+//
+// Copyright (C) 2019-2020 The Radicle Team <dev@radicle.xyz>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 or
+// later as published by the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::vcs::git::Author;
+use git2::Oid;
+
+/// The default notes ref, used by `git notes` when `core.notesRef` is not
+/// configured.
+pub const DEFAULT_NOTES_REF: &str = "refs/notes/commits";
+
+/// A note attached to an object, usually a commit, under a notes ref such as
+/// `refs/notes/commits`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Note {
+    /// The notes ref the note was found under, e.g. `refs/notes/commits`.
+    pub notes_ref: String,
+    /// The `Oid` of the note's blob.
+    pub id: Oid,
+    /// The `Oid` of the object the note is attached to.
+    pub target: Oid,
+    /// The author of the note.
+    pub author: Author,
+    /// The committer of the note.
+    pub committer: Author,
+    /// The message of the note. Invalid UTF-8 is replaced, as for commit
+    /// messages.
+    pub message: String,
+}
+
+impl Note {
+    pub(crate) fn new(notes_ref: &str, target: Oid, note: &git2::Note) -> Self {
+        Note {
+            notes_ref: notes_ref.to_string(),
+            id: note.id(),
+            target,
+            author: Author::from(note.author()),
+            committer: Author::from(note.committer()),
+            message: String::from_utf8_lossy(note.message_bytes()).into_owned(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::vcs::git::{fixture::TempRepository, *};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn reads_notes() -> Result<(), Error> {
+        let repo = TempRepository::new()?;
+        let head = repo.empty_commit("First", 0, &[])?;
+        repo.0.reference("refs/heads/master", head, true, "")?;
+        let sig = git2::Signature::new("Alice", "alice@example.com", &Time::new(0, 0))?;
+        repo.0.note(&sig, &sig, None, head, "LGTM", false)?;
+        repo.0.note(
+            &sig,
+            &sig,
+            Some("refs/notes/ci"),
+            head,
+            "build: passed",
+            false,
+        )?;
+
+        let browser = Browser::new(&repo, Branch::local("master"))?;
+        assert_eq!(
+            browser.list_notes_refs()?,
+            vec![
+                "refs/notes/ci".to_string(),
+                "refs/notes/commits".to_string()
+            ]
+        );
+
+        let note = browser.note(head, None)?.expect("missing default note");
+        assert_eq!(note.notes_ref, note::DEFAULT_NOTES_REF);
+        assert_eq!(note.target, head);
+        assert_eq!(note.message, "LGTM");
+        assert_eq!(note.author.name, "Alice");
+
+        assert_eq!(browser.note(head, Some("refs/notes/review"))?, None);
+
+        let messages = browser
+            .notes(Branch::local("master"))?
+            .into_iter()
+            .map(|note| note.message)
+            .collect::<Vec<_>>();
+        assert_eq!(messages, vec!["build: passed", "LGTM"]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,335 @@
+// This file is part of radicle-surf
+// <https://github.com/radicle-dev/radicle-surf>
+//
+// Copyright (C) 2019-2020 The Radicle Team <dev@radicle.xyz>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 or
+// later as published by the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Options for opening a [`Repository`] other than by its exact path.
+
+use crate::vcs::git::{error::Error, Repository};
+use git2::{ErrorCode, RepositoryOpenFlags};
+use std::{
+    ffi::OsString,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Options for finding and opening a [`Repository`], in the style of
+/// [`std::fs::OpenOptions`].
+///
+/// By default, only the exact path given to [`OpenOptions::open`] is opened,
+/// which is either a `.git` directory, a bare repository or a working copy
+/// containing a `.git` directory, like [`Repository::new`] does.
+///
+/// # Examples
+///
+/// ```
+/// use radicle_surf::vcs::git::{Branch, Browser, OpenOptions};
+/// # use std::error::Error;
+///
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// let repo = OpenOptions::new()
+///     .discover(true)
+///     .read_only(true)
+///     .open("./data/git-platinum/src")?;
+/// let browser = Browser::new(&repo, Branch::local("master"))?;
+/// assert_eq!(
+///     browser.get().first().summary,
+///     "Add files with special characters in their filenames (#5)"
+/// );
+/// #
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct OpenOptions {
+    discover: bool,
+    cross_filesystem: bool,
+    ceiling_dirs: Vec<PathBuf>,
+    bare: bool,
+    from_env: bool,
+    worktree: Option<String>,
+    read_only: bool,
+}
+
+impl OpenOptions {
+    /// Options opening the exact path given, see [`OpenOptions`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Look for the repository in the given directory and then in each of its
+    /// parents, like `git` does when run from a subdirectory.
+    pub fn discover(mut self, discover: bool) -> Self {
+        self.discover = discover;
+        self
+    }
+
+    /// Keep discovering the repository in the parents of a directory on a
+    /// different filesystem, which stops the search by default.
+    pub fn cross_filesystem(mut self, cross_filesystem: bool) -> Self {
+        self.cross_filesystem = cross_filesystem;
+        self
+    }
+
+    /// Stop discovering the repository before entering `dir`.
+    pub fn ceiling_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.ceiling_dirs.push(dir.into());
+        self
+    }
+
+    /// Open the repository as bare, ignoring its working copy and loading its
+    /// configuration lazily.
+    pub fn bare(mut self, bare: bool) -> Self {
+        self.bare = bare;
+        self
+    }
+
+    /// Honour the environment variables `git` uses to locate repositories:
+    ///
+    /// * `GIT_DIR`, which is opened instead of the given path, without
+    ///   discovery.
+    /// * `GIT_COMMON_DIR`, which the repository must share its objects and
+    ///   references with. Since libgit2 cannot override where a repository
+    ///   keeps them, this is only checked.
+    /// * `GIT_CEILING_DIRECTORIES`, which adds to the
+    ///   [`OpenOptions::ceiling_dir`]s.
+    /// * `GIT_NAMESPACE`, which the repository is switched to.
+    pub fn from_env(mut self, from_env: bool) -> Self {
+        self.from_env = from_env;
+        self
+    }
+
+    /// Open the linked worktree called `name` of the repository, rather than
+    /// the repository itself.
+    pub fn worktree(mut self, name: impl Into<String>) -> Self {
+        self.worktree = Some(name.into());
+        self
+    }
+
+    /// Open the repository without its working copy, as [`OpenOptions::bare`]
+    /// does, so that the index and the files of a working copy are never
+    /// read, locked or refreshed.
+    pub fn read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    /// Open the repository at `path` with these options.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::RepositoryNotFound`], if there is no repository at `path`,
+    ///   or above it when discovering
+    /// * [`Error::WorktreeNotFound`], if the repository has no worktree of
+    ///   the requested name
+    /// * [`Error::InvalidWorktree`], if the worktree was moved or deleted
+    /// * [`Error::CommonDirMismatch`], if `GIT_COMMON_DIR` is not the common
+    ///   directory of the repository
+    /// * [`Error::Git`]
+    pub fn open(&self, path: impl AsRef<Path>) -> Result<Repository, Error> {
+        self.open_with_env(path.as_ref(), |key| std::env::var_os(key))
+    }
+
+    pub(super) fn open_with_env<V>(&self, path: &Path, var: V) -> Result<Repository, Error>
+    where
+        V: Fn(&str) -> Option<OsString>,
+    {
+        let var = |key| if self.from_env { var(key) } else { None };
+
+        let mut flags = RepositoryOpenFlags::empty();
+        if !self.discover {
+            flags |= RepositoryOpenFlags::NO_SEARCH;
+        }
+        if self.cross_filesystem {
+            flags |= RepositoryOpenFlags::CROSS_FS;
+        }
+        if self.bare {
+            flags |= RepositoryOpenFlags::BARE;
+        }
+
+        let path = match var("GIT_DIR") {
+            Some(git_dir) => {
+                flags |= RepositoryOpenFlags::NO_SEARCH | RepositoryOpenFlags::NO_DOTGIT;
+                PathBuf::from(git_dir)
+            },
+            None => path.to_path_buf(),
+        };
+        let mut ceiling_dirs = self.ceiling_dirs.clone();
+        if let Some(dirs) = var("GIT_CEILING_DIRECTORIES") {
+            ceiling_dirs.extend(std::env::split_paths(&dirs));
+        }
+
+        let mut repo = git2::Repository::open_ext(&path, flags, &ceiling_dirs).map_err(|err| {
+            if err.code() == ErrorCode::NotFound {
+                Error::RepositoryNotFound(path.clone())
+            } else {
+                err.into()
+            }
+        })?;
+
+        if let Some(name) = &self.worktree {
+            // libgit2 does not report missing worktrees as `NotFound`.
+            if !repo.worktrees()?.iter().any(|found| found == Some(name)) {
+                return Err(Error::WorktreeNotFound(name.clone()));
+            }
+            let worktree = repo.find_worktree(name)?;
+            worktree.validate().map_err(|err| Error::InvalidWorktree {
+                name: name.clone(),
+                reason: err.message().to_string(),
+            })?;
+            repo = git2::Repository::open_from_worktree(&worktree)?;
+        }
+
+        if let Some(expected) = var("GIT_COMMON_DIR") {
+            let expected = PathBuf::from(expected);
+            if !same_dir(&common_dir(&repo), &expected) {
+                return Err(Error::CommonDirMismatch {
+                    git_dir: repo.path().to_path_buf(),
+                    common_dir: expected,
+                });
+            }
+        }
+
+        if self.read_only && !repo.is_bare() {
+            // libgit2 only opens a working copy as bare through its `.git`
+            // directory, which is known by now.
+            let git_dir = repo.path().to_path_buf();
+            repo = git2::Repository::open_ext(
+                &git_dir,
+                RepositoryOpenFlags::NO_SEARCH | RepositoryOpenFlags::BARE,
+                &[] as &[&Path],
+            )?;
+        }
+
+        if let Some(namespace) = var("GIT_NAMESPACE") {
+            repo.set_namespace(&namespace.to_string_lossy())?;
+        }
+
+        Ok(Repository(repo))
+    }
+}
+
+/// The directory holding the objects and references of `repo`, which for a
+/// linked worktree is the `.git` directory of the main working copy.
+fn common_dir(repo: &git2::Repository) -> PathBuf {
+    if repo.is_worktree() {
+        if let Ok(dir) = fs::read_to_string(repo.path().join("commondir")) {
+            return repo.path().join(dir.trim_end());
+        }
+    }
+    repo.path().to_path_buf()
+}
+
+fn same_dir(left: &Path, right: &Path) -> bool {
+    match (fs::canonicalize(left), fs::canonicalize(right)) {
+        (Ok(left), Ok(right)) => left == right,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::vcs::git::{fixture, *};
+    use pretty_assertions::assert_eq;
+    use std::{ffi::OsString, path::Path};
+
+    fn open_in(
+        options: &OpenOptions,
+        path: &Path,
+        env: &[(&str, &Path)],
+    ) -> Result<Repository, Error> {
+        options.open_with_env(path, |key| {
+            env.iter()
+                .find(|(name, _)| *name == key)
+                .map(|(_, value)| OsString::from(value))
+        })
+    }
+
+    #[test]
+    fn opens_repositories_and_worktrees() -> Result<(), Error> {
+        let dir = fixture::temp_dir()?;
+        let path = dir.path().to_path_buf();
+        let main = path.join("main");
+        let subdir = main.join("src").join("bin");
+        {
+            let repo = git2::Repository::init(&main)?;
+            let sig = git2::Signature::new("Alice", "alice@example.com", &Time::new(0, 0))?;
+            let tree = repo.treebuilder(None)?.write()?;
+            let tree = repo.find_tree(tree)?;
+            repo.commit(Some("HEAD"), &sig, &sig, "First", &tree, &[])?;
+            repo.worktree("feature", &path.join("feature"), None)?;
+            std::fs::create_dir_all(&subdir).expect("failed to create a subdirectory");
+        }
+
+        // Only the exact path is opened by default.
+        assert_eq!(
+            OpenOptions::new().open(&subdir).err(),
+            Some(Error::RepositoryNotFound(subdir.clone()))
+        );
+        let repo = OpenOptions::new().discover(true).open(&subdir)?;
+        assert!(repo.0.workdir().is_some());
+        assert_eq!(
+            OpenOptions::new()
+                .discover(true)
+                .ceiling_dir(&path)
+                .open(&path)
+                .err(),
+            Some(Error::RepositoryNotFound(path.clone()))
+        );
+
+        let repo = OpenOptions::new().bare(true).open(main.join(".git"))?;
+        assert!(repo.0.is_bare());
+
+        let repo = OpenOptions::new().read_only(true).open(&main)?;
+        assert!(repo.0.is_bare());
+        assert_eq!(repo.0.workdir(), None);
+
+        let repo = OpenOptions::new().worktree("feature").open(&main)?;
+        assert!(repo.0.is_worktree());
+        assert_eq!(
+            OpenOptions::new().worktree("missing").open(&main).err(),
+            Some(Error::WorktreeNotFound("missing".to_string()))
+        );
+
+        // The environment is only honoured when asked for.
+        let common_dir = main.join(".git");
+        let git_dir = common_dir.join("worktrees").join("feature");
+        let env = [
+            ("GIT_DIR", git_dir.as_path()),
+            ("GIT_COMMON_DIR", common_dir.as_path()),
+        ];
+        assert!(!open_in(&OpenOptions::new(), &main, &env)?.0.is_worktree());
+        let repo = open_in(&OpenOptions::new().from_env(true), &main, &env)?;
+        assert!(repo.0.is_worktree());
+
+        let env = [
+            ("GIT_DIR", git_dir.as_path()),
+            ("GIT_COMMON_DIR", path.as_path()),
+        ];
+        assert_eq!(
+            open_in(&OpenOptions::new().from_env(true), &main, &env).err(),
+            Some(Error::CommonDirMismatch {
+                git_dir: repo.0.path().to_path_buf(),
+                common_dir: path.clone(),
+            })
+        );
+
+        let env = [("GIT_NAMESPACE", Path::new("golden"))];
+        let repo = open_in(&OpenOptions::new().from_env(true), &main, &env)?;
+        assert_eq!(repo.0.namespace(), Some("golden"));
+
+        Ok(())
+    }
+}
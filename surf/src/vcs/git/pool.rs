@@ -0,0 +1,315 @@
+// This file is part of radicle-surf
+// <https://github.com/radicle-dev/radicle-surf>
+//
+// Copyright (C) 2019-2020 The Radicle Team <dev@radicle.xyz>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 or
+// later as published by the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Owned access to a repository for multi-threaded programs.
+//!
+//! A [`Browser`] borrows its repository and cannot be sent to another thread,
+//! while a `git2::Repository` can be sent but not shared. A [`RepositoryPool`]
+//! keeps a few opened repositories around and lends each of them to one
+//! thread at a time, and a [`BrowserHandle`] describes what to browse, so that
+//! a `Browser` can be built wherever it is needed.
+
+use crate::vcs::{
+    git::{error::Error, Browser, Cache, Namespace, Repository, RepositoryRef, Rev},
+    Vcs as _,
+};
+use std::{
+    ops::Deref,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+/// The number of idle repositories a [`RepositoryPool`] keeps by default.
+pub const DEFAULT_CAPACITY: usize = 4;
+
+/// A pool of opened instances of the same repository.
+///
+/// Each instance is only used by one thread at a time, and is opened when all
+/// others are in use. At most `capacity` instances are kept once they are
+/// returned to the pool.
+pub struct RepositoryPool {
+    path: PathBuf,
+    capacity: usize,
+    idle: Mutex<Vec<git2::Repository>>,
+}
+
+impl RepositoryPool {
+    /// Create a pool for the repository at `path`, keeping at most
+    /// [`DEFAULT_CAPACITY`] idle instances.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Git`], if the repository cannot be opened
+    pub fn new(path: impl Into<PathBuf>) -> Result<Self, Error> {
+        Self::with_capacity(path, DEFAULT_CAPACITY)
+    }
+
+    /// Create a pool for the repository at `path`, keeping at most `capacity`
+    /// idle instances.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Git`], if the repository cannot be opened
+    pub fn with_capacity(path: impl Into<PathBuf>, capacity: usize) -> Result<Self, Error> {
+        let path = path.into();
+        let repo = git2::Repository::open(&path)?;
+        Ok(Self {
+            path,
+            capacity,
+            idle: Mutex::new(vec![repo]),
+        })
+    }
+
+    /// The path the repositories are opened from.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Get an idle repository, or open a new one if there is none. It is
+    /// returned to the pool when dropped.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Git`], if the repository cannot be opened
+    pub fn get(&self) -> Result<PooledRepository<'_>, Error> {
+        let repo = match self.lock().pop() {
+            Some(repo) => repo,
+            None => git2::Repository::open(&self.path)?,
+        };
+        Ok(PooledRepository {
+            pool: self,
+            repo: Some(Repository::from(repo)),
+        })
+    }
+
+    /// The number of idle repositories in the pool.
+    pub fn idle(&self) -> usize {
+        self.lock().len()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<git2::Repository>> {
+        // The list of idle repositories is valid even if a thread panicked
+        // while holding the lock.
+        self.idle
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl std::fmt::Debug for RepositoryPool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "RepositoryPool({})", self.path.display())
+    }
+}
+
+/// A [`Repository`] lent by a [`RepositoryPool`], which is returned to it when
+/// dropped.
+pub struct PooledRepository<'a> {
+    pool: &'a RepositoryPool,
+    repo: Option<Repository>,
+}
+
+impl<'a> Deref for PooledRepository<'a> {
+    type Target = Repository;
+
+    fn deref(&self) -> &Self::Target {
+        self.repo
+            .as_ref()
+            .expect("the repository is only taken when dropped")
+    }
+}
+
+impl<'a> Drop for PooledRepository<'a> {
+    fn drop(&mut self) {
+        if let Some(Repository(repo)) = self.repo.take() {
+            // A `Browser` may have switched the namespace, which would
+            // otherwise leak into the next use of the repository.
+            if repo.remove_namespace().is_err() {
+                return;
+            }
+            let mut idle = self.pool.lock();
+            if idle.len() < self.pool.capacity {
+                idle.push(repo);
+            }
+        }
+    }
+}
+
+impl<'a> std::fmt::Debug for PooledRepository<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "PooledRepository({})", self.pool.path.display())
+    }
+}
+
+/// An owned description of what to browse in a repository, which can be
+/// cloned, sent to other threads and held across `.await` points.
+///
+/// A `BrowserHandle` does not hold a [`Browser`] itself, but builds one from a
+/// pooled repository for each call to [`BrowserHandle::with_browser`].
+///
+/// # Examples
+///
+/// ```
+/// use radicle_surf::vcs::git::{error, Branch, BrowserHandle, RepositoryPool};
+/// use std::{sync::Arc, thread};
+/// # use std::error::Error;
+///
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// let pool = Arc::new(RepositoryPool::new("./data/git-platinum")?);
+/// let handle = BrowserHandle::new(pool).rev(Branch::local("master"));
+///
+/// let summary = thread::spawn(move || {
+///     handle.with_browser(|browser| Ok::<_, error::Error>(browser.get().first().summary.clone()))
+/// })
+/// .join()
+/// .expect("the thread panicked")?;
+///
+/// assert_eq!(summary, "Add files with special characters in their filenames (#5)");
+/// #
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct BrowserHandle {
+    pool: Arc<RepositoryPool>,
+    namespace: Option<Namespace>,
+    rev: Option<Rev>,
+    cache: Option<Cache>,
+}
+
+impl BrowserHandle {
+    /// Browse the default branch of the repository, see [`Browser::default`].
+    pub fn new(pool: Arc<RepositoryPool>) -> Self {
+        Self {
+            pool,
+            namespace: None,
+            rev: None,
+            cache: None,
+        }
+    }
+
+    /// Browse within `namespace`. Without a revision, the branch the
+    /// namespace's `HEAD` points to is browsed.
+    pub fn namespace(mut self, namespace: Namespace) -> Self {
+        self.namespace = Some(namespace);
+        self
+    }
+
+    /// Browse the history of `rev`.
+    pub fn rev(mut self, rev: impl Into<Rev>) -> Self {
+        self.rev = Some(rev.into());
+        self
+    }
+
+    /// Share `cache` between the browsers built from this handle and its
+    /// clones, see [`Cache`].
+    pub fn cache(mut self, cache: Cache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// The pool the repositories are taken from.
+    pub fn pool(&self) -> &Arc<RepositoryPool> {
+        &self.pool
+    }
+
+    /// Build a [`Browser`] on a pooled repository and run `f` with it. The
+    /// repository is returned to the pool afterwards.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Git`]
+    /// * Any error returned by `f`
+    pub fn with_browser<T, E, F>(&self, f: F) -> Result<T, E>
+    where
+        E: From<Error>,
+        F: FnOnce(&mut Browser<'_>) -> Result<T, E>,
+    {
+        let repo = self.pool.get()?;
+        let mut browser = self.browser(repo.as_ref())?;
+        f(&mut browser)
+    }
+
+    fn browser<'a>(&self, mut repository: RepositoryRef<'a>) -> Result<Browser<'a>, Error> {
+        repository.cache = self.cache.clone();
+        if let Some(namespace) = &self.namespace {
+            // As for `Browser::new_with_namespace`, the references need to be
+            // loaded before switching namespace.
+            let _ = repository.repo_ref.references()?;
+            repository.switch_namespace(&namespace.to_string())?;
+        }
+        let rev = match &self.rev {
+            Some(rev) => rev.clone(),
+            None => repository.default_branch()?.into(),
+        };
+        let history = repository.get_history(rev)?;
+        Ok(Browser::init(repository, history))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::vcs::git::*;
+    use pretty_assertions::assert_eq;
+    use std::{convert::TryFrom, sync::Arc, thread};
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn handles_are_send_and_sync() {
+        assert_send_sync::<RepositoryPool>();
+        assert_send_sync::<BrowserHandle>();
+    }
+
+    #[test]
+    fn browses_from_many_threads() -> Result<(), Error> {
+        let pool = Arc::new(RepositoryPool::with_capacity("./data/git-platinum", 2)?);
+        let golden = BrowserHandle::new(pool.clone())
+            .namespace(Namespace::try_from("golden")?)
+            .rev(Branch::local("master"));
+
+        let threads = (0..4)
+            .map(|_| {
+                let golden = golden.clone();
+                thread::spawn(move || {
+                    golden.with_browser(|browser| {
+                        browser.branch(Branch::local("banana"))?;
+                        Ok::<_, Error>(browser.get().first().id)
+                    })
+                })
+            })
+            .collect::<Vec<_>>();
+        for thread in threads {
+            assert_eq!(
+                thread.join().expect("the thread panicked")?,
+                Oid::from_str("27acd68c7504755aa11023300890bb85bbd69d45")?
+            );
+        }
+        assert!(pool.idle() <= 2);
+
+        // Namespaces do not leak into later uses of the pooled repositories.
+        let names = BrowserHandle::new(pool.clone())
+            .rev(Branch::local("master"))
+            .with_browser(|browser| {
+                assert_eq!(browser.which_namespace()?, None);
+                browser.list_branches(RefScope::Local)
+            })?;
+        assert_eq!(names.len(), 2);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,255 @@
+// This file is part of radicle-surf
+// <https://github.com/radicle-dev/radicle-surf>
+//
+// Copyright (C) 2019-2020 The Radicle Team <dev@radicle.xyz>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 or
+// later as published by the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Snapshots of the references of a namespace, and the updates between two
+//! snapshots, for noticing what changed in a repository since it was last
+//! looked at.
+
+use git2::Oid;
+use std::{collections::BTreeMap, iter::FromIterator};
+
+#[cfg(feature = "serialize")]
+use serde::{
+    de::Error as _, ser::SerializeStruct as _, Deserialize, Deserializer, Serialize, Serializer,
+};
+
+/// The references of a namespace at some point in time, mapping their names
+/// within the namespace, e.g. `refs/heads/master`, to the objects they point
+/// to.
+///
+/// With the `serialize` feature, a `RefSnapshot` is (de)serialised as a map
+/// from names to hex-encoded `Oid`s, so that it can be stored between polls.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RefSnapshot {
+    refs: BTreeMap<String, Oid>,
+}
+
+impl RefSnapshot {
+    /// Create an empty `RefSnapshot`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the object the reference `name` pointed to, if it existed.
+    pub fn get(&self, name: &str) -> Option<Oid> {
+        self.refs.get(name).copied()
+    }
+
+    /// Iterate over the references, sorted by name.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, Oid)> {
+        self.refs.iter().map(|(name, oid)| (name.as_str(), *oid))
+    }
+
+    /// The number of references in the snapshot.
+    pub fn len(&self) -> usize {
+        self.refs.len()
+    }
+
+    /// Whether the snapshot has no references.
+    pub fn is_empty(&self) -> bool {
+        self.refs.is_empty()
+    }
+}
+
+impl FromIterator<(String, Oid)> for RefSnapshot {
+    fn from_iter<I: IntoIterator<Item = (String, Oid)>>(iter: I) -> Self {
+        Self {
+            refs: iter.into_iter().collect(),
+        }
+    }
+}
+
+#[cfg(feature = "serialize")]
+impl Serialize for RefSnapshot {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_map(self.iter().map(|(name, oid)| (name, oid.to_string())))
+    }
+}
+
+#[cfg(feature = "serialize")]
+impl<'de> Deserialize<'de> for RefSnapshot {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        BTreeMap::<String, String>::deserialize(deserializer)?
+            .into_iter()
+            .map(|(name, oid)| {
+                Oid::from_str(&oid)
+                    .map(|oid| (name, oid))
+                    .map_err(D::Error::custom)
+            })
+            .collect()
+    }
+}
+
+/// How a reference changed between two [`RefSnapshot`]s, as found by
+/// [`crate::vcs::git::Browser::diff_ref_snapshots`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RefUpdate {
+    /// The reference did not exist in the old snapshot.
+    Created {
+        /// The name of the reference.
+        name: String,
+        /// The object the reference points to.
+        new: Oid,
+    },
+    /// The reference no longer exists in the new snapshot.
+    Deleted {
+        /// The name of the reference.
+        name: String,
+        /// The object the reference pointed to.
+        old: Oid,
+    },
+    /// The reference moved to a descendant of the commit it pointed to.
+    FastForwarded {
+        /// The name of the reference.
+        name: String,
+        /// The object the reference pointed to.
+        old: Oid,
+        /// The object the reference points to.
+        new: Oid,
+    },
+    /// The reference moved anywhere else, rewriting its history. This is also
+    /// the case if either object is not in the repository anymore, or does
+    /// not peel to a commit.
+    ForceUpdated {
+        /// The name of the reference.
+        name: String,
+        /// The object the reference pointed to.
+        old: Oid,
+        /// The object the reference points to.
+        new: Oid,
+    },
+}
+
+impl RefUpdate {
+    /// The name of the updated reference.
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Created { name, .. } => name,
+            Self::Deleted { name, .. } => name,
+            Self::FastForwarded { name, .. } => name,
+            Self::ForceUpdated { name, .. } => name,
+        }
+    }
+
+    /// The object the reference pointed to, unless it was created.
+    pub fn old_oid(&self) -> Option<Oid> {
+        match self {
+            Self::Created { .. } => None,
+            Self::Deleted { old, .. } => Some(*old),
+            Self::FastForwarded { old, .. } => Some(*old),
+            Self::ForceUpdated { old, .. } => Some(*old),
+        }
+    }
+
+    /// The object the reference points to, unless it was deleted.
+    pub fn new_oid(&self) -> Option<Oid> {
+        match self {
+            Self::Created { new, .. } => Some(*new),
+            Self::Deleted { .. } => None,
+            Self::FastForwarded { new, .. } => Some(*new),
+            Self::ForceUpdated { new, .. } => Some(*new),
+        }
+    }
+}
+
+#[cfg(feature = "serialize")]
+impl Serialize for RefUpdate {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let kind = match self {
+            Self::Created { .. } => "created",
+            Self::Deleted { .. } => "deleted",
+            Self::FastForwarded { .. } => "fastForwarded",
+            Self::ForceUpdated { .. } => "forceUpdated",
+        };
+
+        let mut state = serializer.serialize_struct("RefUpdate", 4)?;
+        state.serialize_field("type", kind)?;
+        state.serialize_field("name", self.name())?;
+        state.serialize_field("old", &self.old_oid().map(|oid| oid.to_string()))?;
+        state.serialize_field("new", &self.new_oid().map(|oid| oid.to_string()))?;
+        state.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::vcs::git::{fixture::TempRepository, *};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn classifies_updates() -> Result<(), Error> {
+        let repo = TempRepository::new()?;
+        let first = repo.empty_commit("First", 0, &[])?;
+        let second = repo.empty_commit("Second", 0, &[first])?;
+        let other = repo.empty_commit("Other", 0, &[first])?;
+
+        repo.0.reference("refs/heads/master", first, true, "")?;
+        repo.0.reference("refs/heads/feature", second, true, "")?;
+        repo.0.reference("refs/tags/v1", first, true, "")?;
+
+        let browser = Browser::new(&repo, Branch::local("master"))?;
+        let old = browser.ref_snapshot()?;
+
+        repo.0.reference("refs/heads/master", second, true, "")?;
+        repo.0.reference("refs/heads/feature", other, true, "")?;
+        repo.0.find_reference("refs/tags/v1")?.delete()?;
+        repo.0.reference("refs/heads/new", other, true, "")?;
+
+        let new = browser.ref_snapshot()?;
+        assert_eq!(
+            browser.diff_ref_snapshots(&old, &new)?,
+            vec![
+                RefUpdate::ForceUpdated {
+                    name: "refs/heads/feature".to_string(),
+                    old: second,
+                    new: other,
+                },
+                RefUpdate::FastForwarded {
+                    name: "refs/heads/master".to_string(),
+                    old: first,
+                    new: second,
+                },
+                RefUpdate::Created {
+                    name: "refs/heads/new".to_string(),
+                    new: other,
+                },
+                RefUpdate::Deleted {
+                    name: "refs/tags/v1".to_string(),
+                    old: first,
+                },
+            ]
+        );
+
+        #[cfg(feature = "serialize")]
+        {
+            let json = serde_json::to_string(&new).unwrap();
+            let parsed: RefSnapshot = serde_json::from_str(&json).unwrap();
+            assert_eq!(parsed, new);
+        }
+
+        Ok(())
+    }
+}
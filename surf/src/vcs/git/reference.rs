@@ -24,12 +24,178 @@ use crate::vcs::git::{repo::RepositoryRef, BranchName, Namespace, TagName};
 pub(super) mod glob;
 
 /// A revision within the repository.
+///
+/// Besides single commits, a `Rev` can select a range of commits, in which case
+/// the [`crate::vcs::git::History`] built from it contains exactly the commits
+/// in that range. A `Rev` can be parsed from the revision syntax understood by
+/// `git log`.
+///
+/// # Examples
+///
+/// ```
+/// use radicle_surf::vcs::git::{Branch, Rev, TagName};
+/// use std::str::FromStr;
+///
+/// assert_eq!(
+///     Rev::from_str("refs/tags/v0.1.0..refs/heads/master"),
+///     Ok(Rev::range(TagName::new("v0.1.0"), Branch::local("master"))),
+/// );
+///
+/// assert_eq!(
+///     Rev::from_str("master ^dev ^v0.1.0"),
+///     Ok(Rev::Range {
+///         include: vec![Rev::spec("master")],
+///         exclude: vec![Rev::spec("dev"), Rev::spec("v0.1.0")],
+///     }),
+/// );
+///
+/// assert_eq!(Rev::from_str("master~2"), Ok(Rev::spec("master~2")));
+/// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Rev {
     /// A reference to a branch or tag.
     Ref(Ref),
     /// A particular commit identifier.
     Oid(git2::Oid),
+    /// A revision expression resolving to a single commit, e.g. `v0.1.0~2`,
+    /// `master^2` or `dev@{upstream}`.
+    ///
+    /// The name the expression starts from is looked up in the current
+    /// namespace first.
+    Spec(String),
+    /// The commits reachable from any of `include` but from none of `exclude`,
+    /// i.e. `A..B` or `B ^X ^Y`.
+    Range {
+        /// The revisions whose history is included.
+        include: Vec<Rev>,
+        /// The revisions whose history is excluded.
+        exclude: Vec<Rev>,
+    },
+    /// The commits reachable from either `left` or `right`, but not from both,
+    /// i.e. `A...B`.
+    Symmetric {
+        /// The left-hand side of the symmetric difference.
+        left: Box<Rev>,
+        /// The right-hand side of the symmetric difference.
+        right: Box<Rev>,
+    },
+}
+
+impl Rev {
+    /// Create a [`Rev::Spec`] from a revision expression.
+    pub fn spec(spec: impl Into<String>) -> Self {
+        Self::Spec(spec.into())
+    }
+
+    /// The commits reachable from `to` but not from `from`, i.e. `from..to`.
+    pub fn range(from: impl Into<Rev>, to: impl Into<Rev>) -> Self {
+        Self::Range {
+            include: vec![to.into()],
+            exclude: vec![from.into()],
+        }
+    }
+
+    /// The commits reachable from either `left` or `right`, but not from both,
+    /// i.e. `left...right`.
+    pub fn symmetric(left: impl Into<Rev>, right: impl Into<Rev>) -> Self {
+        Self::Symmetric {
+            left: Box::new(left.into()),
+            right: Box::new(right.into()),
+        }
+    }
+
+    /// Parse a revision which is not a range: a fully qualified ref name, a
+    /// full SHA1, or otherwise a revision expression.
+    fn parse_single(s: &str) -> Result<Self, ParseError> {
+        if s.is_empty() || s.starts_with('^') || s.contains("..") {
+            return Err(ParseError::MalformedRev(s.to_owned()));
+        }
+
+        if let Ok(reference) = s.parse::<Ref>() {
+            return Ok(Self::Ref(reference));
+        }
+
+        if s.len() == 40 {
+            if let Ok(oid) = git2::Oid::from_str(s) {
+                return Ok(Self::Oid(oid));
+            }
+        }
+
+        Ok(Self::Spec(s.to_owned()))
+    }
+}
+
+impl fmt::Display for Rev {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Ref(reference) => write!(f, "{}", reference),
+            Self::Oid(oid) => write!(f, "{}", oid),
+            Self::Spec(spec) => write!(f, "{}", spec),
+            Self::Range { include, exclude } => match (include.as_slice(), exclude.as_slice()) {
+                ([to], [from]) => write!(f, "{}..{}", from, to),
+                _ => {
+                    let mut first = true;
+                    for rev in include {
+                        if !first {
+                            write!(f, " ")?;
+                        }
+                        write!(f, "{}", rev)?;
+                        first = false;
+                    }
+                    for rev in exclude {
+                        write!(f, " ^{}", rev)?;
+                    }
+                    Ok(())
+                },
+            },
+            Self::Symmetric { left, right } => write!(f, "{}...{}", left, right),
+        }
+    }
+}
+
+impl str::FromStr for Rev {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let words = s.split_whitespace().collect::<Vec<_>>();
+
+        match words.as_slice() {
+            [] => Err(ParseError::MalformedRev(s.to_owned())),
+            [word] => {
+                // An empty side of a range stands for `HEAD`, as in git.
+                let side = |side: &str| {
+                    if side.is_empty() {
+                        Ok(Self::spec("HEAD"))
+                    } else {
+                        Self::parse_single(side)
+                    }
+                };
+
+                if let Some((left, right)) = word.split_once("...") {
+                    Ok(Self::symmetric(side(left)?, side(right)?))
+                } else if let Some((from, to)) = word.split_once("..") {
+                    Ok(Self::range(side(from)?, side(to)?))
+                } else {
+                    Self::parse_single(word)
+                }
+            },
+            words => {
+                let mut include = vec![];
+                let mut exclude = vec![];
+                for word in words {
+                    match word.strip_prefix('^') {
+                        Some(excluded) => exclude.push(Self::parse_single(excluded)?),
+                        None => include.push(Self::parse_single(word)?),
+                    }
+                }
+
+                if include.is_empty() {
+                    return Err(ParseError::MalformedRev(s.to_owned()));
+                }
+                Ok(Self::Range { include, exclude })
+            },
+        }
+    }
 }
 
 impl<R> From<R> for Rev
@@ -78,6 +244,12 @@ pub enum Ref {
         /// moi/refs/remotes/origin/master`.
         reference: Box<Ref>,
     },
+    /// Any other fully qualified reference, e.g. `refs/notes/commits`,
+    /// `refs/pull/1/head`, `refs/rad/id` or `refs/changes/01/1/1`.
+    Qualified {
+        /// The full name of the reference, starting with `refs/`.
+        name: String,
+    },
 }
 
 impl Ref {
@@ -94,13 +266,27 @@ impl Ref {
         ref_namespace
     }
 
+    /// Create a [`Ref::Qualified`] from the full name of a reference, e.g.
+    /// `refs/pull/1/head`.
+    pub fn qualified(name: impl Into<String>) -> Self {
+        Self::Qualified { name: name.into() }
+    }
+
+    /// Remove any [`Ref::Namespace`] layers from a `Ref`.
+    pub(crate) fn unnamespaced(self) -> Self {
+        match self {
+            Self::Namespace { reference, .. } => reference.unnamespaced(),
+            reference => reference,
+        }
+    }
+
     /// We try to find a [`git2::Reference`] based off of a `Ref` by turning the
     /// ref into a fully qualified ref (e.g. refs/remotes/**/master).
     pub fn find_ref<'a>(
         &self,
         repo: &RepositoryRef<'a>,
     ) -> Result<git2::Reference<'a>, git2::Error> {
-        repo.repo_ref.find_reference(&self.to_string())
+        repo.repo_ref.0.find_reference(&self.to_string())
     }
 }
 
@@ -114,14 +300,75 @@ impl fmt::Display for Ref {
                 namespace,
                 reference,
             } => write!(f, "refs/namespaces/{}/{}", namespace, reference),
+            Self::Qualified { name } => write!(f, "{}", name),
+        }
+    }
+}
+
+/// Glob patterns selecting references by their name within the current
+/// namespace, e.g. `refs/heads/*`. As for git, `*` also matches `/`.
+///
+/// References are selected if they match any of the included patterns, or
+/// `refs/*` if none are given, and none of the excluded patterns.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RefPatterns {
+    include: Vec<String>,
+    exclude: Vec<String>,
+}
+
+impl RefPatterns {
+    /// Select all references, i.e. `refs/*`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Include the references matching `glob`.
+    pub fn include(mut self, glob: impl Into<String>) -> Self {
+        self.include.push(glob.into());
+        self
+    }
+
+    /// Exclude the references matching `glob`.
+    pub fn exclude(mut self, glob: impl Into<String>) -> Self {
+        self.exclude.push(glob.into());
+        self
+    }
+
+    pub(crate) fn included(&self) -> Vec<String> {
+        if self.include.is_empty() {
+            vec!["refs/*".to_string()]
+        } else {
+            self.include.clone()
         }
     }
+
+    pub(crate) fn is_excluded(&self, name: &str) -> bool {
+        self.exclude.iter().any(|glob| glob::matches(glob, name))
+    }
 }
 
+/// A reference found by [`crate::vcs::git::Browser::list_refs`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RefInfo {
+    /// The name of the reference within the current namespace, e.g.
+    /// `refs/heads/master`.
+    pub name: String,
+    /// The object the reference points to, after following symbolic
+    /// references. For annotated tags this is the tag object.
+    pub target: git2::Oid,
+    /// The commit the reference peels to, if any.
+    pub peeled: Option<git2::Oid>,
+}
+
+/// Errors that can occur when parsing a [`Ref`] or a [`Rev`].
 #[derive(Debug, PartialEq, Error)]
 pub enum ParseError {
+    /// The string is not a fully qualified ref name.
     #[error("the ref provided '{0}' was malformed")]
     MalformedRef(String),
+    /// The string is not a revision or revision range.
+    #[error("the revision provided '{0}' was malformed")]
+    MalformedRev(String),
 }
 
 pub mod parser {
@@ -135,6 +382,7 @@ pub mod parser {
     const REMOTES: &str = "refs/remotes/";
     const TAGS: &str = "refs/tags/";
     const NAMESPACES: &str = "refs/namespaces/";
+    const REFS: &str = "refs/";
 
     named!(heads, tag!(HEADS));
     named!(remotes, tag!(REMOTES));
@@ -181,11 +429,31 @@ pub mod parser {
         })
     }
 
+    /// Parse any reference outside of the prefixes that have their own
+    /// variant, i.e. `refs/heads/`, `refs/remotes/`, `refs/tags/` and
+    /// `refs/namespaces/`.
+    pub fn qualified(s: &str) -> Result<Ref, Error<'_>> {
+        bytes::complete::tag(REFS)(s).and_then(|(rest, _)| {
+            let reserved = [HEADS, REMOTES, TAGS, NAMESPACES]
+                .iter()
+                .any(|prefix| s.starts_with(prefix));
+            if reserved || rest.is_empty() || rest.split('/').any(str::is_empty) {
+                Err(nom::Err::Error(nom::error::Error::new(
+                    s,
+                    nom::error::ErrorKind::Verify,
+                )))
+            } else {
+                Ok(Ref::Qualified { name: s.to_owned() })
+            }
+        })
+    }
+
     pub fn parse(s: &str) -> Result<Ref, nom::Err<nom::error::Error<&str>>> {
         local(s)
             .or_else(|_| remote(s))
             .or_else(|_| tag(s))
             .or_else(|_| namespace(s))
+            .or_else(|_| qualified(s))
     }
 }
 
@@ -200,7 +468,18 @@ impl str::FromStr for Ref {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::str::FromStr;
+    use crate::{
+        file_system::Directory,
+        vcs::git::{
+            fixture::TempRepository,
+            Branch,
+            Browser,
+            EphemeralRepository,
+            Error,
+            Namespace,
+        },
+    };
+    use std::{convert::TryFrom, str::FromStr};
 
     #[test]
     fn parse_ref() -> Result<(), ParseError> {
@@ -285,6 +564,24 @@ mod tests {
             })
         );
 
+        assert_eq!(
+            Ref::from_str("refs/pull/1/head"),
+            Ok(Ref::qualified("refs/pull/1/head"))
+        );
+
+        assert_eq!(
+            Ref::from_str("refs/namespaces/moi/refs/notes/commits"),
+            Ok(Ref::Namespace {
+                namespace: "moi".to_string(),
+                reference: Box::new(Ref::qualified("refs/notes/commits")),
+            })
+        );
+
+        assert_eq!(
+            Ref::from_str("refs/rad//id"),
+            Err(ParseError::MalformedRef("refs/rad//id".to_owned())),
+        );
+
         assert_eq!(
             Ref::from_str("refs/remotes/master"),
             Err(ParseError::MalformedRef("refs/remotes/master".to_owned())),
@@ -299,4 +596,117 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn parse_rev() -> Result<(), ParseError> {
+        let master = Rev::Ref(Ref::LocalBranch {
+            name: BranchName::new("master"),
+        });
+
+        assert_eq!(Rev::from_str("refs/heads/master"), Ok(master.clone()));
+
+        assert_eq!(
+            Rev::from_str("a0dd9122d33dff2a35f564d564db127152c88e02"),
+            Ok(Rev::Oid(
+                git2::Oid::from_str("a0dd9122d33dff2a35f564d564db127152c88e02").unwrap()
+            ))
+        );
+
+        assert_eq!(Rev::from_str("HEAD^2"), Ok(Rev::spec("HEAD^2")));
+
+        assert_eq!(
+            Rev::from_str("dev@{upstream}"),
+            Ok(Rev::spec("dev@{upstream}"))
+        );
+
+        assert_eq!(
+            Rev::from_str("v0.1.0..refs/heads/master"),
+            Ok(Rev::range(Rev::spec("v0.1.0"), master.clone()))
+        );
+
+        assert_eq!(
+            Rev::from_str("dev..."),
+            Ok(Rev::symmetric(Rev::spec("dev"), Rev::spec("HEAD")))
+        );
+
+        assert_eq!(
+            Rev::from_str("^dev refs/heads/master"),
+            Ok(Rev::Range {
+                include: vec![master],
+                exclude: vec![Rev::spec("dev")],
+            })
+        );
+
+        assert_eq!(
+            Rev::from_str("^dev"),
+            Err(ParseError::MalformedRev("^dev".to_owned()))
+        );
+
+        assert_eq!(
+            Rev::from_str("a..b..c"),
+            Err(ParseError::MalformedRev("b..c".to_owned()))
+        );
+
+        assert_eq!(
+            Rev::from_str("dev..master").map(|rev| rev.to_string()),
+            Ok("dev..master".to_owned())
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn list_refs_skips_dangling_symbolic_refs() -> Result<(), Error> {
+        let repo = EphemeralRepository::new()?;
+        let head = repo.commit(Branch::local("master"), &Directory::root(), "First")?;
+        repo.set_head(Branch::local("master"))?;
+        repo.0
+            .reference_symbolic("refs/heads/gone", "refs/heads/missing", true, "")?;
+
+        let browser = Browser::default(&repo)?;
+        let refs = browser.list_refs(&RefPatterns::new().include("refs/heads/*"))?;
+        assert_eq!(
+            refs.iter()
+                .map(|info| (info.name.as_str(), info.target))
+                .collect::<Vec<_>>(),
+            vec![("refs/heads/master", head)]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn browses_qualified_refs() -> Result<(), Error> {
+        let repo = TempRepository::new()?;
+        let first = repo.empty_commit("First", 0, &[])?;
+        let second = repo.empty_commit("Second", 0, &[first])?;
+        repo.0.reference("refs/heads/master", first, true, "")?;
+        repo.0.reference("refs/pull/1/head", second, true, "")?;
+        repo.0
+            .reference("refs/namespaces/ns/refs/heads/master", first, true, "")?;
+        repo.0
+            .reference("refs/namespaces/ns/refs/rad/id", second, true, "")?;
+
+        let mut browser = Browser::new(&repo, Branch::local("master"))?;
+        assert_eq!(
+            browser.list_qualified_refs("refs/pull")?,
+            vec![Ref::qualified("refs/pull/1/head")]
+        );
+
+        browser.rev(Ref::qualified("refs/pull/1/head"))?;
+        assert_eq!(browser.get().first().id, second);
+
+        let mut browser =
+            browser.switch_namespace(&Namespace::try_from("ns")?, Branch::local("master"))?;
+        assert_eq!(browser.get().first().id, first);
+        assert_eq!(
+            browser.list_qualified_refs("refs/rad")?,
+            vec![Ref::qualified("refs/rad/id")]
+        );
+
+        browser.rev(Ref::qualified("refs/rad/id"))?;
+        assert_eq!(browser.get().first().id, second);
+
+        Ok(())
+    }
 }
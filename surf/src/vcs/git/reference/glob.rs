@@ -54,6 +54,18 @@ pub enum RefGlob {
         /// the provided remote name will be used.
         remote: Option<String>,
     },
+    /// When calling [`RefGlob::references`] this will return the references via
+    /// the glob `refs/notes/*`.
+    Notes,
+    /// When calling [`RefGlob::references`] this will return the references via
+    /// the glob `{prefix}/*`, e.g. `refs/pull/*`.
+    Qualified {
+        /// The prefix of the references, e.g. `refs/pull`.
+        prefix: String,
+    },
+    /// When calling [`RefGlob::references`] this will return the references
+    /// matching the given glob, e.g. `refs/heads/feature/*`.
+    Pattern(String),
     /// refs/namespaces/**
     Namespace,
 }
@@ -108,13 +120,13 @@ impl RefGlob {
 
         Ok(match self {
             Self::Branch => {
-                let remotes = repo.repo_ref.references_glob(&format!(
+                let remotes = repo.repo_ref.0.references_glob(&format!(
                     "{}{}",
                     namespace_glob,
                     Self::RemoteBranch { remote: None }
                 ))?;
 
-                let locals = repo.repo_ref.references_glob(&format!(
+                let locals = repo.repo_ref.0.references_glob(&format!(
                     "{}{}",
                     namespace_glob,
                     &Self::LocalBranch
@@ -124,13 +136,13 @@ impl RefGlob {
                 }
             },
             Self::Tag => {
-                let remotes = repo.repo_ref.references_glob(&format!(
+                let remotes = repo.repo_ref.0.references_glob(&format!(
                     "{}{}",
                     namespace_glob,
                     Self::RemoteTag { remote: None }
                 ))?;
 
-                let locals = repo.repo_ref.references_glob(&format!(
+                let locals = repo.repo_ref.0.references_glob(&format!(
                     "{}{}",
                     namespace_glob,
                     &Self::LocalTag
@@ -142,6 +154,7 @@ impl RefGlob {
             other => References {
                 inner: vec![repo
                     .repo_ref
+                    .0
                     .references_glob(&format!("{}{}", namespace_glob, other,))?],
             },
         })
@@ -160,6 +173,9 @@ impl fmt::Display for RefGlob {
                 }
             },
             Self::LocalTag => write!(f, "refs/tags/*"),
+            Self::Notes => write!(f, "refs/notes/*"),
+            Self::Pattern(pattern) => write!(f, "{}", pattern),
+            Self::Qualified { prefix } => write!(f, "{}/*", prefix.trim_end_matches('/')),
             Self::RemoteTag { remote } => {
                 let remote = match remote {
                     Some(remote) => remote.as_ref(),
@@ -181,3 +197,163 @@ impl fmt::Display for RefGlob {
         }
     }
 }
+
+/// Match `name` against the glob `pattern`, where `*` matches any sequence of
+/// characters, including `/`, in the same way as the globs used by
+/// [`RefGlob::references`].
+pub fn matches(pattern: &str, name: &str) -> bool {
+    matches_bytes(pattern.as_bytes(), name.as_bytes())
+}
+
+/// Match `name` against `pattern` from left to right. On a mismatch, the last
+/// `*` seen is made to match one more character and matching resumes after
+/// it, so no more than one `*` is ever retried.
+fn matches_bytes(pattern: &[u8], name: &[u8]) -> bool {
+    let (mut pattern_rest, mut name_rest) = (pattern, name);
+    // The pattern following the last `*`, and the name it is matched from.
+    let mut star: Option<(&[u8], &[u8])> = None;
+
+    loop {
+        match token(pattern_rest) {
+            Some((Token::Star, rest)) => {
+                star = Some((rest, name_rest));
+                pattern_rest = rest;
+                continue;
+            },
+            Some((token, rest)) => {
+                if let Some((c, name_tail)) = name_rest.split_first() {
+                    if token.matches(*c) {
+                        pattern_rest = rest;
+                        name_rest = name_tail;
+                        continue;
+                    }
+                }
+            },
+            None if name_rest.is_empty() => return true,
+            None => {},
+        }
+
+        match star {
+            Some((after_star, [_, retry @ ..])) => {
+                star = Some((after_star, retry));
+                pattern_rest = after_star;
+                name_rest = retry;
+            },
+            _ => return false,
+        }
+    }
+}
+
+/// A single element of a glob pattern.
+enum Token<'a> {
+    /// `*`, matching any sequence of characters.
+    Star,
+    /// `?`, matching any character.
+    Any,
+    /// A character class, e.g. `[a-z]`.
+    Class(Class<'a>),
+    /// Any other character, matching itself.
+    Literal(u8),
+}
+
+impl<'a> Token<'a> {
+    /// Whether the token matches the character `c`. A `*` matches any
+    /// character.
+    fn matches(&self, c: u8) -> bool {
+        match self {
+            Self::Star | Self::Any => true,
+            Self::Class(class) => class.contains(c),
+            Self::Literal(literal) => *literal == c,
+        }
+    }
+}
+
+/// Split the first token off `pattern`, or `None` if it is empty. An
+/// unterminated `[` is taken literally.
+fn token(pattern: &[u8]) -> Option<(Token<'_>, &[u8])> {
+    let (first, rest) = pattern.split_first()?;
+    Some(match first {
+        b'*' => (Token::Star, rest),
+        b'?' => (Token::Any, rest),
+        b'[' => match class(rest) {
+            Some((class, rest)) => (Token::Class(class), rest),
+            None => (Token::Literal(b'['), rest),
+        },
+        c => (Token::Literal(*c), rest),
+    })
+}
+
+/// A character class, e.g. `[a-z]` or `[!0-9]`.
+struct Class<'a> {
+    negated: bool,
+    members: &'a [u8],
+}
+
+impl<'a> Class<'a> {
+    fn contains(&self, c: u8) -> bool {
+        let mut members = self.members;
+        let mut found = false;
+        while let Some((first, rest)) = members.split_first() {
+            match rest {
+                [b'-', last, rest @ ..] => {
+                    found |= *first <= c && c <= *last;
+                    members = rest;
+                },
+                _ => {
+                    found |= *first == c;
+                    members = rest;
+                },
+            }
+        }
+        found != self.negated
+    }
+}
+
+/// Parse a character class given the pattern following the opening `[`.
+/// Returns the class and the rest of the pattern, or `None` if the class is not
+/// terminated.
+fn class(pattern: &[u8]) -> Option<(Class<'_>, &[u8])> {
+    let (negated, body) = match pattern.split_first() {
+        Some((b'!', body)) | Some((b'^', body)) => (true, body),
+        _ => (false, pattern),
+    };
+    // A `]` directly after the opening bracket is part of the class.
+    let end = body.iter().skip(1).position(|c| *c == b']')? + 1;
+    Some((
+        Class {
+            negated,
+            members: &body[..end],
+        },
+        &body[end + 1..],
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::matches;
+
+    #[test]
+    fn test_matches() {
+        assert!(matches("refs/heads/*", "refs/heads/master"));
+        assert!(matches("refs/heads/*", "refs/heads/feature/#1194"));
+        assert!(matches("refs/*/HEAD", "refs/remotes/origin/HEAD"));
+        assert!(matches("refs/tags/v0.?.0", "refs/tags/v0.1.0"));
+        assert!(matches("refs/tags/v[0-9].*", "refs/tags/v1.0.0"));
+        assert!(!matches("refs/tags/v[!0-9].*", "refs/tags/v1.0.0"));
+        assert!(matches("refs/[]]", "refs/]"));
+        assert!(matches("refs/[", "refs/["));
+        assert!(!matches("refs/heads/*", "refs/tags/v1.0.0"));
+        assert!(!matches("refs/heads/master", "refs/heads/master2"));
+        assert!(matches("refs/**/HEAD", "refs/remotes/origin/HEAD"));
+        assert!(matches("refs/*/*/master", "refs/remotes/origin/master"));
+        assert!(!matches("refs/*/*/master", "refs/heads/master"));
+    }
+
+    #[test]
+    fn test_matches_many_stars() {
+        let name = format!("refs/{}", "a".repeat(256));
+        let pattern = format!("refs/{}b", "a*".repeat(32));
+        assert!(!matches(&pattern, &name));
+        assert!(matches(&format!("refs/{}", "a*".repeat(32)), &name));
+    }
+}
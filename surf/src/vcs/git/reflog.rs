@@ -0,0 +1,112 @@
+// This file is part of radicle-surf
+// <https://github.com/radicle-dev/radicle-surf>
+//
+// Copyright (C) 2019-2020 The Radicle Team <dev@radicle.xyz>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 or
+// later as published by the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::vcs::git::Author;
+use git2::Oid;
+
+/// An entry of the reflog of a reference, recording one update of it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReflogEntry {
+    /// The `Oid` the reference pointed to before the update. This is the zero
+    /// `Oid` when the update created the reference.
+    pub old: Oid,
+    /// The `Oid` the reference pointed to after the update.
+    pub new: Oid,
+    /// Who updated the reference, and when.
+    pub committer: Author,
+    /// The message recorded for the update, e.g. `commit: Add README`.
+    pub message: Option<String>,
+    /// Whether `new` is a descendant of `old`, i.e. the update did not rewrite
+    /// history. Updates creating the reference are fast-forwards, and updates
+    /// involving commits that are no longer in the repository are not.
+    pub fast_forward: bool,
+}
+
+impl ReflogEntry {
+    /// Whether the update created the reference.
+    pub fn is_creation(&self) -> bool {
+        self.old.is_zero()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::vcs::git::{fixture::TempRepository, *};
+    use pretty_assertions::assert_eq;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn marks_forced_updates() -> Result<(), Error> {
+        let repo = TempRepository::new()?;
+        repo.0
+            .config()?
+            .set_str("core.logAllRefUpdates", "always")?;
+        let pruned = Oid::from_str("0123456789abcdef0123456789abcdef01234567")?;
+        let first = repo.empty_commit("First", 0, &[])?;
+        let second = repo.empty_commit("Second", 0, &[first])?;
+        let rewritten = repo.empty_commit("Rewritten", 0, &[first])?;
+
+        for name in &["refs/heads/master", "refs/namespaces/ns/refs/heads/master"] {
+            repo.0.reference(name, first, true, "branch: Created")?;
+        }
+        repo.0.reference(
+            "refs/namespaces/ns/refs/heads/master",
+            second,
+            true,
+            "fetch",
+        )?;
+        repo.0.reference(
+            "refs/namespaces/ns/refs/heads/master",
+            rewritten,
+            true,
+            "push",
+        )?;
+
+        let sig = git2::Signature::new("Alice", "alice@example.com", &Time::new(0, 0))?;
+        let mut reflog = repo.0.reflog("refs/namespaces/ns/refs/heads/master")?;
+        reflog.append(pruned, &sig, Some("pruned"))?;
+        reflog.write()?;
+
+        let browser = Browser::new_with_namespace(
+            &repo,
+            &Namespace::try_from("ns")?,
+            Branch::local("master"),
+        )?;
+
+        let entries = browser
+            .reflog(Branch::local("master"))?
+            .into_iter()
+            .map(|entry| (entry.old, entry.new, entry.message, entry.fast_forward))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            entries,
+            vec![
+                (rewritten, pruned, Some("pruned".to_string()), false),
+                (second, rewritten, Some("push".to_string()), false),
+                (first, second, Some("fetch".to_string()), true),
+                (
+                    Oid::zero(),
+                    first,
+                    Some("branch: Created".to_string()),
+                    true
+                ),
+            ]
+        );
+
+        Ok(())
+    }
+}
@@ -21,21 +21,45 @@ use crate::{
     vcs,
     vcs::{
         git::{
+            cache::IdentityKey,
             error::*,
-            reference::{glob::RefGlob, Ref, Rev},
+            reference::{glob::RefGlob, Ref, RefInfo, RefPatterns, Rev},
+            AheadBehind,
+            Author,
+            BlameHunk,
             Branch,
+            BranchInfo,
+            BranchName,
+            BranchSort,
+            BranchType,
+            Cache,
             Commit,
+            Description,
             Namespace,
+            NamespaceTree,
+            Note,
             RefScope,
+            RefSnapshot,
+            RefUpdate,
+            ReflogEntry,
             Signature,
             Tag,
+            TagName,
         },
         Vcs,
     },
 };
 use git2::Oid;
 use nonempty::NonEmpty;
-use std::{collections::HashSet, convert::TryFrom, str};
+use once_cell::unsync::OnceCell;
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    convert::TryFrom,
+    fs,
+    ops::Deref,
+    str,
+};
 
 /// This is for flagging to the `file_history` function that it should
 /// stop at the first (i.e. Last) commit it finds for a file.
@@ -52,6 +76,23 @@ pub type History = vcs::History<Commit>;
 /// on the underlying object.
 pub struct Repository(pub(super) git2::Repository);
 
+/// Determines how the author and committer identities of commits are
+/// resolved.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum Identities {
+    /// Use the identities exactly as they are recorded in the commits.
+    #[default]
+    Raw,
+    /// Resolve the identities through the repository's mailmap. This is
+    /// loaded from the `.mailmap` file in the working copy, or from `HEAD`
+    /// for bare repositories, as well as the `mailmap.file` and
+    /// `mailmap.blob` configuration.
+    Mailmap,
+    /// Resolve the identities through the `.mailmap` file found at the given
+    /// revision. If there is no such file the identities are left as is.
+    MailmapAt(Rev),
+}
+
 /// A reference-only `Repository`. This means that we cannot mutate the
 /// underlying `Repository`. Not being able to mutate the `Repository` means
 /// that the functions defined for `RepositoryRef` should be thread-safe.
@@ -61,17 +102,63 @@ pub struct Repository(pub(super) git2::Repository);
 /// Use the `From<&'a git2::Repository>` implementation to construct a
 /// `RepositoryRef`.
 pub struct RepositoryRef<'a> {
-    pub(super) repo_ref: &'a git2::Repository,
+    pub(super) repo_ref: SendRef<'a>,
+    pub(super) identities: Identities,
+    pub(super) mailmap: OnceCell<LoadedMailmap>,
+    pub(super) cache: Option<Cache>,
 }
 
-// RepositoryRef should be safe to transfer across thread boundaries since it
-// only holds a reference to git2::Repository. git2::Repository is also Send
+/// A reference to a `git2::Repository` which can be sent to another thread,
+/// so that [`RepositoryRef`] is `Send` without vouching for its other fields.
+#[derive(Clone, Copy)]
+pub(super) struct SendRef<'a>(pub(super) &'a git2::Repository);
+
+// SendRef should be safe to transfer across thread boundaries since it only
+// holds a reference to git2::Repository. git2::Repository is also Send
 // (see: https://docs.rs/git2/0.13.5/src/git2/repo.rs.html#46)
-unsafe impl<'a> Send for RepositoryRef<'a> {}
+unsafe impl<'a> Send for SendRef<'a> {}
+
+impl<'a> Deref for SendRef<'a> {
+    type Target = git2::Repository;
+
+    fn deref(&self) -> &Self::Target {
+        self.0
+    }
+}
+
+/// The mailmap loaded for the [`Identities`] of a [`RepositoryRef`], if any,
+/// and the key of the commits converted with it in a [`Cache`].
+pub(super) struct LoadedMailmap {
+    mailmap: Option<git2::Mailmap>,
+    key: IdentityKey,
+}
+
+// SAFETY: git2::Mailmap is only !Send because it wraps a raw pointer. The
+// git_mailmap it points to is owned by this value alone and libgit2 keeps no
+// per-thread state for it, so it can be used and freed on another thread.
+// LoadedMailmap stays !Sync, so the mailmap is never used from two threads at
+// once.
+unsafe impl Send for LoadedMailmap {}
+
+impl LoadedMailmap {
+    /// Build the mailmap from `contents`, keyed by those same contents.
+    fn new(contents: &[u8]) -> Result<Self, Error> {
+        let contents = String::from_utf8_lossy(contents);
+        Ok(Self {
+            mailmap: Some(git2::Mailmap::from_buffer(&contents)?),
+            key: IdentityKey::mailmap(contents.as_bytes())?,
+        })
+    }
+}
 
 impl<'a> From<&'a git2::Repository> for RepositoryRef<'a> {
     fn from(repo_ref: &'a git2::Repository) -> Self {
-        RepositoryRef { repo_ref }
+        RepositoryRef {
+            repo_ref: SendRef(repo_ref),
+            identities: Identities::default(),
+            mailmap: OnceCell::new(),
+            cache: None,
+        }
     }
 }
 
@@ -101,6 +188,106 @@ impl<'a> RepositoryRef<'a> {
             })
     }
 
+    /// List the branches within a repository along with their head commit and
+    /// upstream tracking information, in the given order.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Git`]
+    pub fn list_branch_info(
+        &self,
+        scope: RefScope,
+        sort: BranchSort,
+    ) -> Result<Vec<BranchInfo>, Error> {
+        let mailmap = self.mailmap()?;
+        let config = self.repo_ref.config()?;
+        let mut branches = RefGlob::branch(scope).references(self)?.iter().try_fold(
+            vec![],
+            |mut acc, reference| {
+                let reference = reference?;
+                let head = Commit::with_mailmap(reference.peel_to_commit()?, mailmap)?;
+                let branch = Branch::try_from(reference)?;
+                let upstream = match branch.locality {
+                    BranchType::Local => self.upstream(&config, &branch.name),
+                    BranchType::Remote { .. } => None,
+                };
+                let ahead_behind = match &upstream {
+                    Some(upstream) => self.ahead_behind(head.id, upstream)?,
+                    None => None,
+                };
+
+                acc.push(BranchInfo {
+                    branch,
+                    head,
+                    upstream,
+                    ahead_behind,
+                });
+                Ok::<_, Error>(acc)
+            },
+        )?;
+
+        match sort {
+            BranchSort::Name => branches.sort_by_key(|info| info.branch.name()),
+            BranchSort::Activity => branches.sort_by(|a, b| {
+                b.last_activity()
+                    .seconds()
+                    .cmp(&a.last_activity().seconds())
+                    .then_with(|| a.branch.name().cmp(&b.branch.name()))
+            }),
+        }
+
+        Ok(branches)
+    }
+
+    /// The upstream of the local branch `name`, following `git`: the branch
+    /// `branch.<name>.merge` of the remote `branch.<name>.remote`, as mapped
+    /// by the fetch refspecs of the remote. The remote `.` stands for the
+    /// repository itself.
+    fn upstream(&self, config: &git2::Config, name: &BranchName) -> Option<Branch> {
+        let remote = config.get_string(&format!("branch.{}.remote", name)).ok()?;
+        let merge = config.get_string(&format!("branch.{}.merge", name)).ok()?;
+
+        if remote == "." {
+            let merge = merge.strip_prefix("refs/heads/").unwrap_or(&merge);
+            return Some(Branch::local(merge));
+        }
+
+        let tracking = self
+            .repo_ref
+            .find_remote(&remote)
+            .ok()?
+            .refspecs()
+            .filter(|refspec| refspec.direction() == git2::Direction::Fetch)
+            .find(|refspec| refspec.src_matches(&merge))?
+            .transform(&merge)
+            .ok()?;
+        let tracking = tracking.as_str()?;
+
+        if let Some(name) = tracking.strip_prefix(&format!("refs/remotes/{}/", remote)) {
+            Some(Branch::remote(name, &remote))
+        } else if let Some(tracking) = tracking.strip_prefix("refs/remotes/") {
+            let (remote, name) = tracking.split_once('/')?;
+            Some(Branch::remote(name, remote))
+        } else {
+            tracking.strip_prefix("refs/heads/").map(Branch::local)
+        }
+    }
+
+    /// Compare `head` to the head of `upstream` within the current namespace.
+    /// Returns `None` if `upstream` does not exist.
+    fn ahead_behind(&self, head: Oid, upstream: &Branch) -> Result<Option<AheadBehind>, Error> {
+        let prefix = self.namespace_prefix()?;
+        let name = format!("{}{}", prefix, Ref::from(upstream.clone()));
+        let other = match self.repo_ref.find_reference(&name) {
+            Ok(reference) => reference.peel_to_commit()?.id(),
+            Err(err) if err.code() == git2::ErrorCode::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+        let (ahead, behind) = self.repo_ref.graph_ahead_behind(head, other)?;
+
+        Ok(Some(AheadBehind { ahead, behind }))
+    }
+
     /// List the tags within a repository, filtering out ones that do not parse
     /// correctly.
     ///
@@ -118,6 +305,76 @@ impl<'a> RepositoryRef<'a> {
             })
     }
 
+    /// List the references within the current namespace that are selected by
+    /// `patterns`, sorted by name.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Git`]
+    pub fn list_refs(&self, patterns: &RefPatterns) -> Result<Vec<RefInfo>, Error> {
+        let prefix = self.namespace_prefix()?;
+        let mut refs = BTreeMap::new();
+
+        for pattern in patterns.included() {
+            let nested = pattern.starts_with("refs/namespaces/");
+            for reference in RefGlob::Pattern(pattern).references(self)?.iter() {
+                let reference = reference?;
+                let name = str::from_utf8(reference.name_bytes())?;
+                let name = name.strip_prefix(&prefix).unwrap_or(name);
+                if (!nested && name.starts_with("refs/namespaces/"))
+                    || patterns.is_excluded(name)
+                    || refs.contains_key(name)
+                {
+                    continue;
+                }
+
+                let resolved = match reference.resolve() {
+                    Ok(resolved) => resolved,
+                    // A symbolic reference to a missing reference is skipped.
+                    Err(err) if err.code() == git2::ErrorCode::NotFound => continue,
+                    Err(err) => return Err(err.into()),
+                };
+                let target = match resolved.target() {
+                    Some(target) => target,
+                    None => continue,
+                };
+                let peeled = resolved.peel_to_commit().ok().map(|commit| commit.id());
+                refs.insert(
+                    name.to_string(),
+                    RefInfo {
+                        name: name.to_string(),
+                        target,
+                        peeled,
+                    },
+                );
+            }
+        }
+
+        Ok(refs.into_values().collect())
+    }
+
+    /// List the references under `prefix`, e.g. `refs/pull`, within the current
+    /// namespace.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Git`]
+    pub fn list_qualified_refs(&self, prefix: &str) -> Result<Vec<Ref>, Error> {
+        RefGlob::Qualified {
+            prefix: prefix.to_string(),
+        }
+        .references(self)?
+        .iter()
+        .try_fold(vec![], |mut acc, reference| {
+            let reference = reference?;
+            let name = str::from_utf8(reference.name_bytes())?;
+            if let Ok(reference) = name.parse::<Ref>() {
+                acc.push(reference.unnamespaced());
+            }
+            Ok(acc)
+        })
+    }
+
     /// List the namespaces within a repository, filtering out ones that do not
     /// parse correctly.
     ///
@@ -136,6 +393,171 @@ impl<'a> RepositoryRef<'a> {
         Ok(namespaces?.into_iter().collect())
     }
 
+    /// Take a snapshot of the references within the current namespace.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Git`]
+    pub fn ref_snapshot(&self) -> Result<RefSnapshot, Error> {
+        Ok(self
+            .list_refs(&RefPatterns::new())?
+            .into_iter()
+            .map(|info| (info.name, info.target))
+            .collect())
+    }
+
+    /// Compare two snapshots, classifying the updates of the references that
+    /// differ.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Git`]
+    pub fn diff_ref_snapshots(
+        &self,
+        old: &RefSnapshot,
+        new: &RefSnapshot,
+    ) -> Result<Vec<RefUpdate>, Error> {
+        let names = old
+            .iter()
+            .chain(new.iter())
+            .map(|(name, _)| name)
+            .collect::<BTreeSet<_>>();
+
+        let mut updates = vec![];
+        for name in names {
+            let update = match (old.get(name), new.get(name)) {
+                (None, Some(new)) => RefUpdate::Created {
+                    name: name.to_string(),
+                    new,
+                },
+                (Some(old), None) => RefUpdate::Deleted {
+                    name: name.to_string(),
+                    old,
+                },
+                (Some(old), Some(new)) if old != new => {
+                    if self.is_fast_forward(old, new)? {
+                        RefUpdate::FastForwarded {
+                            name: name.to_string(),
+                            old,
+                            new,
+                        }
+                    } else {
+                        RefUpdate::ForceUpdated {
+                            name: name.to_string(),
+                            old,
+                            new,
+                        }
+                    }
+                },
+                _ => continue,
+            };
+            updates.push(update);
+        }
+
+        Ok(updates)
+    }
+
+    /// Whether the commit `new` peels to descends from the one `old` peels to.
+    /// Objects that are missing or do not peel to a commit are never
+    /// fast-forwards.
+    fn is_fast_forward(&self, old: Oid, new: Oid) -> Result<bool, Error> {
+        let peel = |oid| {
+            self.repo_ref
+                .find_object(oid, None)
+                .and_then(|object| object.peel_to_commit())
+                .map(|commit| commit.id())
+                .ok()
+        };
+
+        match (peel(old), peel(new)) {
+            (Some(old), Some(new)) => {
+                Ok(old == new || self.repo_ref.graph_descendant_of(new, old)?)
+            },
+            _ => Ok(false),
+        }
+    }
+
+    /// Build the tree of the namespaces nested within the current namespace.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Git`]
+    pub fn namespace_tree(&self) -> Result<Vec<NamespaceTree>, Error> {
+        let prefix = self.namespace_prefix()?;
+        let base: Vec<String> = self
+            .which_namespace()?
+            .map(|namespace| namespace.values.into())
+            .unwrap_or_default();
+        let mut nodes = BTreeMap::new();
+
+        for reference in RefGlob::Namespace.references(self)?.iter() {
+            let reference = reference?;
+            let full_name = str::from_utf8(reference.name_bytes())?;
+            let mut name = full_name.strip_prefix(&prefix).unwrap_or(full_name);
+            let mut path = base.clone();
+            while let Some((value, rest)) = name
+                .strip_prefix("refs/namespaces/")
+                .and_then(|rest| rest.split_once('/'))
+            {
+                path.push(value.to_string());
+                name = rest;
+                if !nodes.contains_key(&path) {
+                    let namespace = Namespace {
+                        values: NonEmpty::from_vec(path.clone()).ok_or(Error::EmptyNamespace)?,
+                    };
+                    nodes.insert(path.clone(), NamespaceTree::new(namespace));
+                }
+            }
+
+            let node = match nodes.get_mut(&path) {
+                Some(node) => node,
+                None => continue,
+            };
+            if name == "HEAD" {
+                let node_prefix = &full_name[..full_name.len() - name.len()];
+                node.head = reference
+                    .symbolic_target_bytes()
+                    .map(str::from_utf8)
+                    .transpose()?
+                    .and_then(|target| target.strip_prefix(node_prefix))
+                    .and_then(|target| target.strip_prefix("refs/heads/"))
+                    .map(BranchName::new);
+            } else if name.starts_with("refs/") {
+                node.refs += 1;
+                if let Some(branch) = name.strip_prefix("refs/heads/") {
+                    node.heads.push(BranchName::new(branch));
+                }
+            }
+        }
+
+        for node in nodes.values_mut() {
+            node.heads.sort();
+        }
+        Ok(Self::assemble_namespaces(&base, &mut nodes))
+    }
+
+    /// Take the nodes directly nested within `parent` out of `nodes`, along
+    /// with their own nested nodes.
+    fn assemble_namespaces(
+        parent: &[String],
+        nodes: &mut BTreeMap<Vec<String>, NamespaceTree>,
+    ) -> Vec<NamespaceTree> {
+        let keys = nodes
+            .keys()
+            .filter(|key| key.len() == parent.len() + 1 && key.starts_with(parent))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        keys.into_iter()
+            .filter_map(|key| {
+                let children = Self::assemble_namespaces(&key, nodes);
+                nodes
+                    .remove(&key)
+                    .map(|node| NamespaceTree { children, ..node })
+            })
+            .collect()
+    }
+
     pub(super) fn reference<R, P>(&self, reference: R, check: P) -> Result<History, Error>
     where
         R: Into<Ref>,
@@ -160,6 +582,45 @@ impl<'a> RepositoryRef<'a> {
             .and_then(|diff| Diff::try_from(diff).map_err(Error::from))
     }
 
+    /// Get the [`Diff`] between the commits `from` and `to` point to, which are
+    /// resolved regardless of the current namespace.
+    pub(crate) fn diff_refs(&self, from: &Ref, to: &Ref) -> Result<Diff, Error> {
+        self.diff(self.peel_absolute(from)?, self.peel_absolute(to)?)
+    }
+
+    /// Compare the commits `head` and `base` point to, which are resolved
+    /// regardless of the current namespace.
+    pub(crate) fn ahead_behind_refs(&self, head: &Ref, base: &Ref) -> Result<AheadBehind, Error> {
+        let (ahead, behind) = self
+            .repo_ref
+            .graph_ahead_behind(self.peel_absolute(head)?, self.peel_absolute(base)?)?;
+        Ok(AheadBehind { ahead, behind })
+    }
+
+    /// Get the commits reachable from `head` but not from `base`, which are
+    /// resolved regardless of the current namespace.
+    pub(crate) fn commits_between(&self, base: &Ref, head: &Ref) -> Result<Vec<Commit>, Error> {
+        let mailmap = self.mailmap()?;
+        let mut revwalk = self.repo_ref.revwalk()?;
+        revwalk.set_sorting(git2::Sort::TIME)?;
+        revwalk.push(self.peel_absolute(head)?)?;
+        revwalk.hide(self.peel_absolute(base)?)?;
+
+        revwalk
+            .map(|oid| {
+                let commit = self.repo_ref.find_commit(oid?)?;
+                Commit::with_mailmap(commit, mailmap)
+            })
+            .collect()
+    }
+
+    /// Peel `reference` to the commit it points to. Its name is taken as is,
+    /// i.e. it is not resolved within the current namespace, so namespaced
+    /// references of any namespace can be given.
+    pub(super) fn peel_absolute(&self, reference: &Ref) -> Result<Oid, Error> {
+        Ok(reference.find_ref(self)?.peel_to_commit()?.id())
+    }
+
     /// Get the [`Diff`] of a commit with no parents.
     pub fn initial_diff(&self, oid: Oid) -> Result<Diff, Error> {
         self.diff_commits(None, None, oid)
@@ -171,10 +632,232 @@ impl<'a> RepositoryRef<'a> {
         Ok(self.repo_ref.revparse_single(oid)?.id())
     }
 
+    /// Resolve a [`Rev`] to a single commit. Ranges are rejected with
+    /// [`Error::NotASingleRevision`].
     pub(super) fn rev_to_commit(&self, rev: &Rev) -> Result<git2::Commit, Error> {
         match rev {
             Rev::Oid(oid) => Ok(self.repo_ref.find_commit(*oid)?),
             Rev::Ref(reference) => Ok(reference.find_ref(self)?.peel_to_commit()?),
+            Rev::Spec(spec) => self.spec_to_commit(spec),
+            Rev::Range { .. } | Rev::Symmetric { .. } => Err(Error::NotASingleRevision {
+                rev: rev.to_string(),
+            }),
+        }
+    }
+
+    /// Resolve a revision expression, such as `master~2`, looking up the name
+    /// it starts from in the current namespace first.
+    fn spec_to_commit(&self, spec: &str) -> Result<git2::Commit<'_>, Error> {
+        let not_found = |err: git2::Error| match err.code() {
+            git2::ErrorCode::NotFound
+            | git2::ErrorCode::InvalidSpec
+            | git2::ErrorCode::Ambiguous => match self.which_namespace() {
+                Ok(Some(namespace)) => Error::NamespaceRevParseFailure {
+                    namespace,
+                    rev: spec.to_string(),
+                },
+                Ok(None) => Error::RevParseFailure {
+                    rev: spec.to_string(),
+                },
+                Err(err) => err,
+            },
+            _ => Error::Git(err),
+        };
+
+        let split = spec
+            .find(['~', '^'])
+            .into_iter()
+            .chain(spec.find("@{"))
+            .min()
+            .unwrap_or(spec.len());
+        let (name, suffix) = spec.split_at(split);
+
+        if name.is_empty() || name == "HEAD" || suffix.starts_with("@{") {
+            return self
+                .repo_ref
+                .revparse_single(spec)
+                .and_then(|object| object.peel_to_commit())
+                .map_err(not_found);
+        }
+
+        let namespaced = match self.which_namespace()? {
+            None => None,
+            Some(namespace) => vec![
+                Ref::LocalBranch {
+                    name: BranchName::new(name),
+                },
+                Ref::from(TagName::new(name)),
+            ]
+            .into_iter()
+            .find_map(|reference| {
+                reference
+                    .namespaced(namespace.clone())
+                    .find_ref(self)
+                    .and_then(|reference| reference.peel_to_commit())
+                    .ok()
+            }),
+        };
+        let base = match namespaced {
+            Some(commit) => commit,
+            None => self
+                .repo_ref
+                .revparse_single(name)
+                .and_then(|object| object.peel_to_commit())
+                .map_err(not_found)?,
+        };
+
+        if suffix.is_empty() {
+            Ok(base)
+        } else {
+            self.repo_ref
+                .revparse_single(&format!("{}{}", base.id(), suffix))
+                .and_then(|object| object.peel_to_commit())
+                .map_err(not_found)
+        }
+    }
+
+    /// Resolve a revision to a commit, looking references up in the current
+    /// namespace.
+    fn rev_to_oid(&self, rev: &Rev) -> Result<Oid, Error> {
+        match (rev, self.which_namespace()?) {
+            (Rev::Ref(reference), Some(namespace)) => Ok(reference
+                .clone()
+                .namespaced(namespace)
+                .find_ref(self)?
+                .peel_to_commit()?
+                .id()),
+            _ => Ok(self.rev_to_commit(rev)?.id()),
+        }
+    }
+
+    /// Build a [`History`] of the commits reachable from `include` but not from
+    /// `exclude`.
+    fn range_to_history(
+        &self,
+        rev: &Rev,
+        include: &[Oid],
+        exclude: &[Oid],
+    ) -> Result<History, Error> {
+        let mut revwalk = self.repo_ref.revwalk()?;
+        for oid in include {
+            revwalk.push(*oid)?;
+        }
+        for oid in exclude {
+            revwalk.hide(*oid)?;
+        }
+
+        let commits = revwalk.try_fold(vec![], |mut commits, oid| {
+            commits.push(self.load_commit(oid?)?);
+            Ok::<_, Error>(commits)
+        })?;
+
+        NonEmpty::from_vec(commits)
+            .map(vcs::History)
+            .ok_or_else(|| Error::EmptyRange {
+                rev: rev.to_string(),
+            })
+    }
+
+    /// The mailmap used for resolving identities, as configured by the
+    /// [`Identities`] of this `RepositoryRef`. It is loaded on first use and
+    /// kept until the identities change.
+    pub(super) fn mailmap(&self) -> Result<Option<&git2::Mailmap>, Error> {
+        Ok(self.loaded_mailmap()?.mailmap.as_ref())
+    }
+
+    fn loaded_mailmap(&self) -> Result<&LoadedMailmap, Error> {
+        self.mailmap.get_or_try_init(|| self.load_mailmap())
+    }
+
+    /// Choose how the author and committer identities of commits are
+    /// resolved, dropping the mailmap loaded for the previous setting.
+    pub(super) fn set_identities(&mut self, identities: Identities) {
+        self.identities = identities;
+        self.mailmap = OnceCell::new();
+    }
+
+    fn load_mailmap(&self) -> Result<LoadedMailmap, Error> {
+        match &self.identities {
+            Identities::Raw => Ok(LoadedMailmap {
+                mailmap: None,
+                key: IdentityKey::Raw,
+            }),
+            Identities::Mailmap => LoadedMailmap::new(&self.mailmap_contents()?),
+            Identities::MailmapAt(rev) => {
+                let tree = self.rev_to_commit(rev)?.tree()?;
+                let entry = match tree.get_name(".mailmap") {
+                    Some(entry) => entry,
+                    None => {
+                        return Ok(LoadedMailmap {
+                            mailmap: None,
+                            key: IdentityKey::Raw,
+                        })
+                    },
+                };
+                let blob = entry.to_object(self.repo_ref.0)?.peel_to_blob()?;
+                LoadedMailmap::new(blob.content())
+            },
+        }
+    }
+
+    /// The contents of the mailmaps of the repository, in the order `git`
+    /// reads them: `.mailmap` in the working copy, the blob `mailmap.blob`
+    /// (`HEAD:.mailmap` for bare repositories) and the file `mailmap.file`.
+    /// Missing mailmaps are skipped.
+    fn mailmap_contents(&self) -> Result<Vec<u8>, Error> {
+        let config = self.repo_ref.config()?;
+        let workdir = self.repo_ref.workdir();
+        let mut contents = Vec::new();
+        let mut add = |content: &[u8]| {
+            if !contents.is_empty() {
+                contents.push(b'\n');
+            }
+            contents.extend_from_slice(content);
+        };
+
+        if let Some(workdir) = workdir {
+            if let Ok(content) = fs::read(workdir.join(".mailmap")) {
+                add(&content);
+            }
+        }
+
+        let blob = match config.get_string("mailmap.blob") {
+            Ok(blob) => Some(blob),
+            Err(_) if self.repo_ref.is_bare() => Some("HEAD:.mailmap".to_string()),
+            Err(_) => None,
+        };
+        if let Some(blob) = blob {
+            if let Ok(blob) = self
+                .repo_ref
+                .revparse_single(&blob)
+                .and_then(|object| object.peel_to_blob())
+            {
+                add(blob.content());
+            }
+        }
+
+        if let Ok(file) = config.get_path("mailmap.file") {
+            let file = match workdir {
+                Some(workdir) => workdir.join(file),
+                None => file,
+            };
+            if let Ok(content) = fs::read(file) {
+                add(&content);
+            }
+        }
+
+        Ok(contents)
+    }
+
+    /// Convert the commit `oid` with the mailmap of this `RepositoryRef`,
+    /// going through the [`Cache`] if there is one.
+    pub(super) fn load_commit(&self, oid: Oid) -> Result<Commit, Error> {
+        let loaded = self.loaded_mailmap()?;
+        let convert =
+            || Commit::with_mailmap(self.repo_ref.find_commit(oid)?, loaded.mailmap.as_ref());
+        match &self.cache {
+            Some(cache) => cache.commit((oid, loaded.key.clone()), convert),
+            None => convert(),
         }
     }
 
@@ -184,7 +867,7 @@ impl<'a> RepositoryRef<'a> {
 
     /// Get a particular `Commit`.
     pub(super) fn get_commit(&self, oid: Oid) -> Result<git2::Commit<'a>, Error> {
-        let commit = self.repo_ref.find_commit(oid)?;
+        let commit = self.repo_ref.0.find_commit(oid)?;
         Ok(commit)
     }
 
@@ -205,7 +888,7 @@ impl<'a> RepositoryRef<'a> {
     /// a revwalk over the first commit in the reference.
     pub(super) fn commit_to_history(&self, head: git2::Commit) -> Result<History, Error> {
         let head_id = head.id();
-        let mut commits = NonEmpty::new(Commit::try_from(head)?);
+        let mut commits = NonEmpty::new(self.load_commit(head_id)?);
         let mut revwalk = self.repo_ref.revwalk()?;
 
         // Set the revwalk to the head commit
@@ -221,8 +904,7 @@ impl<'a> RepositoryRef<'a> {
                 continue;
             }
 
-            let commit = Commit::try_from(self.repo_ref.find_commit(commit_id)?)?;
-            commits.push(commit);
+            commits.push(self.load_commit(commit_id)?);
         }
 
         Ok(vcs::History(commits))
@@ -277,8 +959,208 @@ impl<'a> RepositoryRef<'a> {
         Ok(contained_branches)
     }
 
+    pub(crate) fn revision_tags(&self, oid: &Oid) -> Result<Vec<Tag>, Error> {
+        let mut contained_tags = vec![];
+
+        RefGlob::LocalTag
+            .references(self)?
+            .iter()
+            .try_for_each(|reference| {
+                let reference = reference?;
+                self.reachable_from(&reference, oid).and_then(|contains| {
+                    if contains {
+                        let tag = Tag::try_from(reference)?;
+                        contained_tags.push(tag);
+                    }
+                    Ok(())
+                })
+            })?;
+
+        Ok(contained_tags)
+    }
+
+    /// Find the local tag nearest to `oid`: of the tagged commits in its
+    /// history, the one with the fewest commits reachable from `oid` but not
+    /// from it. Ties go to annotated tags, then to the first name.
+    pub(crate) fn describe(&self, oid: Oid) -> Result<Option<Description>, Error> {
+        let mut tagged: HashMap<Oid, Vec<(bool, TagName)>> = HashMap::new();
+        for reference in RefGlob::LocalTag.references(self)?.iter() {
+            let reference = reference?;
+            if let Some(target) = peeled_commit(&reference)? {
+                let annotated = reference.peel_to_tag().is_ok();
+                let name = TagName::try_from(reference.name_bytes())?;
+                tagged.entry(target).or_default().push((annotated, name));
+            }
+        }
+        if tagged.is_empty() {
+            return Ok(None);
+        }
+
+        // Across merges, the first tagged commit walked is not necessarily the
+        // nearest, so every tagged commit in the history is a candidate.
+        let mut candidates = vec![];
+        let mut revwalk = self.repo_ref.revwalk()?;
+        revwalk.push(oid)?;
+        for commit in revwalk {
+            let commit = commit?;
+            if let Some(tags) = tagged.remove(&commit) {
+                let distance = self.distance(oid, commit)?;
+                candidates.extend(
+                    tags.into_iter()
+                        .map(|(annotated, tag)| (distance, Reverse(annotated), tag)),
+                );
+            }
+            if tagged.is_empty() {
+                break;
+            }
+        }
+
+        let (distance, Reverse(annotated), tag) = match candidates.into_iter().min() {
+            Some(nearest) => nearest,
+            None => return Ok(None),
+        };
+        let short_id = self.get_commit(oid)?.as_object().short_id()?;
+        Ok(Some(Description {
+            tag,
+            annotated,
+            distance,
+            id: oid,
+            short_id: str::from_utf8(&short_id)?.to_string(),
+        }))
+    }
+
+    pub(crate) fn reflog<R>(&self, reference: R) -> Result<Vec<ReflogEntry>, Error>
+    where
+        R: Into<Ref>,
+    {
+        let reference = match self.which_namespace()? {
+            None => reference.into(),
+            Some(namespace) => reference.into().namespaced(namespace),
+        };
+        // Ensure the reference exists, since a missing reflog is otherwise
+        // indistinguishable from an empty one.
+        let name = reference.find_ref(self)?;
+        let name = str::from_utf8(name.name_bytes())?;
+        let odb = self.repo_ref.odb()?;
+
+        self.repo_ref
+            .reflog(name)?
+            .iter()
+            .map(|entry| {
+                let old = entry.id_old();
+                let new = entry.id_new();
+                // The commits may have been pruned since the update, in which
+                // case it is not known to be a fast-forward.
+                let fast_forward = old.is_zero()
+                    || old == new
+                    || (odb.exists(old)
+                        && odb.exists(new)
+                        && self.repo_ref.graph_descendant_of(new, old)?);
+
+                Ok(ReflogEntry {
+                    old,
+                    new,
+                    committer: Author::from(entry.committer()),
+                    message: entry
+                        .message_bytes()
+                        .map(|message| String::from_utf8_lossy(message).into_owned()),
+                    fast_forward,
+                })
+            })
+            .collect()
+    }
+
+    /// Get the branch that `HEAD` points to in the current namespace.
+    pub(crate) fn default_branch(&self) -> Result<Branch, Error> {
+        self.symbolic_branch("HEAD")
+    }
+
+    /// Get the branch that `refs/remotes/<remote>/HEAD` points to in the
+    /// current namespace.
+    pub(crate) fn remote_default_branch(&self, remote: &str) -> Result<Branch, Error> {
+        self.symbolic_branch(&format!("refs/remotes/{}/HEAD", remote))
+    }
+
+    /// Follow the symbolic reference `name`, within the current namespace, to
+    /// the branch it points to.
+    fn symbolic_branch(&self, name: &str) -> Result<Branch, Error> {
+        let prefix = self.namespace_prefix()?;
+        let reference = self
+            .repo_ref
+            .find_reference(&format!("{}{}", prefix, name))?;
+        let target = reference
+            .symbolic_target_bytes()
+            .map(str::from_utf8)
+            .transpose()?
+            .ok_or_else(|| Error::NoDefaultBranch(name.to_string()))?;
+
+        let target = target.strip_prefix(&prefix).unwrap_or(target);
+        match target.parse::<Ref>() {
+            Ok(Ref::LocalBranch { name }) => Ok(Branch::local(name.name())),
+            Ok(Ref::RemoteBranch { remote, name }) => Ok(Branch::remote(name.name(), &remote)),
+            _ => Err(Error::NoDefaultBranch(name.to_string())),
+        }
+    }
+
+    /// The prefix of the references in the current namespace, e.g.
+    /// `refs/namespaces/golden/`.
+    fn namespace_prefix(&self) -> Result<String, Error> {
+        Ok(self
+            .which_namespace()?
+            .map(|namespace| {
+                namespace
+                    .values
+                    .iter()
+                    .map(|value| format!("refs/namespaces/{}/", value))
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    /// List the notes refs of the current namespace, e.g.
+    /// `refs/notes/commits`.
+    pub(crate) fn list_notes_refs(&self) -> Result<Vec<String>, Error> {
+        let prefix = self.namespace_prefix()?;
+        RefGlob::Notes
+            .references(self)?
+            .iter()
+            .try_fold(vec![], |mut acc, reference| {
+                let reference = reference?;
+                let name = str::from_utf8(reference.name_bytes())?;
+                acc.push(name.strip_prefix(&prefix).unwrap_or(name).to_string());
+                Ok(acc)
+            })
+    }
+
+    /// Read the note attached to `oid` under `notes_ref`, defaulting to the
+    /// repository's default notes ref.
+    pub(crate) fn note(&self, oid: Oid, notes_ref: Option<&str>) -> Result<Option<Note>, Error> {
+        let notes_ref = match notes_ref {
+            Some(notes_ref) => notes_ref.to_string(),
+            None => self.repo_ref.note_default_ref()?,
+        };
+        let namespaced = format!("{}{}", self.namespace_prefix()?, notes_ref);
+
+        match self.repo_ref.find_note(Some(&namespaced), oid) {
+            Ok(note) => Ok(Some(Note::new(&notes_ref, oid, &note))),
+            Err(err) if err.code() == git2::ErrorCode::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Count the commits reachable from `head` but not from `base`.
+    fn distance(&self, head: Oid, base: Oid) -> Result<usize, Error> {
+        let mut revwalk = self.repo_ref.revwalk()?;
+        revwalk.push(head)?;
+        revwalk.hide(base)?;
+        revwalk.try_fold(0, |count, oid| oid.map(|_| count + 1).map_err(Error::from))
+    }
+
     fn reachable_from(&self, reference: &git2::Reference, oid: &Oid) -> Result<bool, Error> {
-        let other = reference.peel_to_commit()?.id();
+        let other = match peeled_commit(reference)? {
+            Some(other) => other,
+            None => return Ok(false),
+        };
         let is_descendant = self.repo_ref.graph_descendant_of(other, *oid)?;
 
         Ok(other == *oid || is_descendant)
@@ -303,7 +1185,7 @@ impl<'a> RepositoryRef<'a> {
             let parent = self.repo_ref.find_commit(parent_id)?;
             let paths = self.diff_commit_and_parents(path, &parent)?;
             if let Some(_path) = paths {
-                commits.push(Commit::try_from(parent)?);
+                commits.push(self.load_commit(parent_id)?);
                 match &commit_history {
                     CommitHistory::Last => break,
                     CommitHistory::Full => {},
@@ -314,6 +1196,75 @@ impl<'a> RepositoryRef<'a> {
         Ok(commits)
     }
 
+    /// Attribute the lines of the file at `path` in `commit` to the commits
+    /// which last changed them.
+    pub(super) fn blame(
+        &self,
+        path: &file_system::Path,
+        commit: Oid,
+    ) -> Result<Vec<BlameHunk>, Error> {
+        let mut opts = git2::BlameOptions::new();
+        opts.newest_commit(commit);
+        let file = path.to_string();
+        let blame = self
+            .repo_ref
+            .blame_file(std::path::Path::new(&file), Some(&mut opts))
+            .map_err(|err| match err.code() {
+                git2::ErrorCode::NotFound => Error::PathNotFound(path.clone()),
+                _ => Error::Git(err),
+            })?;
+
+        blame
+            .iter()
+            .map(|hunk| {
+                Ok(BlameHunk {
+                    commit: self.load_commit(hunk.final_commit_id())?,
+                    start_line: hunk.final_start_line(),
+                    lines: hunk.lines_in_hunk(),
+                })
+            })
+            .collect()
+    }
+
+    /// Get the latest commit of the history starting at `commit` which touched
+    /// `path`, going through the [`Cache`] if there is one.
+    pub(super) fn last_commit(
+        &self,
+        path: &file_system::Path,
+        commit: Commit,
+    ) -> Result<Option<Commit>, Error> {
+        let cache = match &self.cache {
+            Some(cache) => cache,
+            None => {
+                let file_history = self.file_history(path, CommitHistory::Last, commit)?;
+                return Ok(file_history.into_iter().next());
+            },
+        };
+
+        let last = cache.last_commit(commit.id, path, || {
+            let file_history = self.file_history(path, CommitHistory::Last, commit)?;
+            Ok(file_history.first().map(|commit| commit.id))
+        })?;
+        match last {
+            Some(oid) => Ok(Some(self.load_commit(oid)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Count the lines added and removed by a commit, compared to its first
+    /// parent. Merge commits are not counted, since their changes are already
+    /// accounted for by the commits being merged.
+    pub(super) fn line_stats(&self, commit: &Commit) -> Result<(usize, usize), Error> {
+        if commit.parents.len() > 1 {
+            return Ok((0, 0));
+        }
+
+        let stats = self
+            .diff_commits(None, commit.parents.first().copied(), commit.id)?
+            .stats()?;
+        Ok((stats.insertions(), stats.deletions()))
+    }
+
     fn diff_commit_and_parents(
         &self,
         path: &file_system::Path,
@@ -354,6 +1305,37 @@ impl<'a> RepositoryRef<'a> {
 
         Ok(diff)
     }
+
+    /// Write the files of `directory` into the object database as blobs,
+    /// and its sub-directories as trees, returning the `Oid` of the tree of
+    /// `directory` itself. See [`crate::vcs::git::CommitBuilder`] for
+    /// committing it.
+    ///
+    /// Files are written as regular, non-executable files.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Git`]
+    pub fn write_directory(&self, directory: &file_system::Directory) -> Result<Oid, Error> {
+        let mut builder = self.repo_ref.treebuilder(None)?;
+        for entry in directory.iter() {
+            match entry {
+                file_system::DirectoryContents::File { name, file } => {
+                    let blob = self.repo_ref.blob(&file.contents)?;
+                    builder.insert(name.as_str(), blob, git2::FileMode::Blob.into())?;
+                },
+                file_system::DirectoryContents::Directory(sub_directory) => {
+                    let tree = self.write_directory(&sub_directory)?;
+                    builder.insert(
+                        sub_directory.current().as_str(),
+                        tree,
+                        git2::FileMode::Tree.into(),
+                    )?;
+                },
+            }
+        }
+        Ok(builder.write()?)
+    }
 }
 
 impl<'a> Vcs<Commit, Error> for RepositoryRef<'a> {
@@ -367,6 +1349,37 @@ impl<'a> Vcs<Commit, Error> for RepositoryRef<'a> {
                 let commit = self.get_commit(oid)?;
                 self.commit_to_history(commit)
             },
+            Rev::Spec(ref spec) => {
+                let commit = self.spec_to_commit(spec)?;
+                self.commit_to_history(commit)
+            },
+            Rev::Range {
+                ref include,
+                ref exclude,
+            } => {
+                let include = include
+                    .iter()
+                    .map(|rev| self.rev_to_oid(rev))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let exclude = exclude
+                    .iter()
+                    .map(|rev| self.rev_to_oid(rev))
+                    .collect::<Result<Vec<_>, _>>()?;
+                self.range_to_history(&history_id, &include, &exclude)
+            },
+            Rev::Symmetric {
+                ref left,
+                ref right,
+            } => {
+                let left = self.rev_to_oid(left)?;
+                let right = self.rev_to_oid(right)?;
+                let bases = match self.repo_ref.merge_bases(left, right) {
+                    Ok(bases) => bases.iter().copied().collect(),
+                    Err(err) if err.code() == git2::ErrorCode::NotFound => vec![],
+                    Err(err) => return Err(err.into()),
+                };
+                self.range_to_history(&history_id, &[left, right], &bases)
+            },
         }
     }
 
@@ -397,7 +1410,8 @@ impl<'a> std::fmt::Debug for RepositoryRef<'a> {
 }
 
 impl Repository {
-    /// Open a git repository given its URI.
+    /// Open a git repository given its URI. See [`crate::vcs::git::OpenOptions`]
+    /// for discovering repositories and opening worktrees.
     ///
     /// # Errors
     ///
@@ -413,7 +1427,12 @@ impl Repository {
     /// [`Repository`], the one returend by [`Repository::new`], into a
     /// [`RepositoryRef`].
     pub fn as_ref(&'_ self) -> RepositoryRef<'_> {
-        RepositoryRef { repo_ref: &self.0 }
+        RepositoryRef {
+            repo_ref: SendRef(&self.0),
+            identities: Identities::default(),
+            mailmap: OnceCell::new(),
+            cache: None,
+        }
     }
 }
 
@@ -444,3 +1463,194 @@ impl std::fmt::Debug for Repository {
         write!(f, ".git")
     }
 }
+
+/// Peel `reference`, following any chain of tags, to the commit it points to.
+/// Returns `None` if it points to a tree or a blob instead.
+fn peeled_commit(reference: &git2::Reference) -> Result<Option<Oid>, Error> {
+    let object = reference.peel(git2::ObjectType::Any)?;
+    Ok(object.as_commit().map(|commit| commit.id()))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::vcs::git::{fixture::TempRepository, *};
+    use pretty_assertions::assert_eq;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn resolves_identities() -> Result<(), Error> {
+        let repo = TempRepository::new()?;
+        {
+            let mailmap = repo
+                .0
+                .blob(b"Alice <alice@example.com> <alice@old.example.com>\n")?;
+            let mut tree = repo.0.treebuilder(None)?;
+            tree.insert(".mailmap", mailmap, 0o100_644)?;
+            let tree = repo.0.find_tree(tree.write()?)?;
+
+            let old = git2::Signature::new("alice", "alice@old.example.com", &Time::new(0, 0))?;
+            let new = git2::Signature::new("Alice", "alice@example.com", &Time::new(1, 0))?;
+            let first = repo.0.commit(None, &old, &old, "First", &tree, &[])?;
+            let first = repo.0.find_commit(first)?;
+            repo.0.commit(
+                Some("refs/heads/master"),
+                &new,
+                &new,
+                "Second",
+                &tree,
+                &[&first],
+            )?;
+            repo.0.set_head("refs/heads/master")?;
+        }
+
+        let mut browser = Browser::new(&repo, Branch::local("master"))?;
+        assert_eq!(browser.get().0.last().author.email, "alice@old.example.com");
+        assert_eq!(browser.get_stats()?.contributors, 2);
+
+        browser.identities(Identities::Mailmap)?;
+        let authors = browser
+            .get()
+            .iter()
+            .map(|commit| (commit.author.name.clone(), commit.author.email.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            authors,
+            vec![
+                ("Alice".to_string(), "alice@example.com".to_string()),
+                ("Alice".to_string(), "alice@example.com".to_string()),
+            ]
+        );
+        assert_eq!(browser.get_stats()?.contributors, 1);
+
+        let blame = browser.blame(file_system::unsound::path::new(".mailmap"))?;
+        assert_eq!(blame.len(), 1);
+        assert_eq!(blame[0].commit.id, browser.get().0.last().id);
+        assert_eq!(blame[0].commit.author.email, "alice@example.com");
+
+        browser.identities(Identities::Raw)?;
+        assert_eq!(browser.get_stats()?.contributors, 2);
+
+        browser.identities(Identities::MailmapAt(Branch::local("master").into()))?;
+        assert_eq!(browser.get_stats()?.contributors, 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn keeps_the_history_when_changing_identities() -> Result<(), Error> {
+        let repo = TempRepository::new()?;
+        let first = repo.empty_commit("First", 0, &[])?;
+        let second = repo.empty_commit("Second", 1, &[first])?;
+        let third = repo.empty_commit("Third", 2, &[second])?;
+        repo.0.reference("refs/heads/master", third, true, "")?;
+
+        let mut browser = Browser::new(&repo, Branch::local("master"))?;
+        browser.rev(Rev::range(first, Branch::local("master")))?;
+        browser.identities(Identities::Mailmap)?;
+        assert_eq!(
+            browser
+                .get()
+                .iter()
+                .map(|commit| commit.id)
+                .collect::<Vec<_>>(),
+            vec![third, second]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn describes_with_the_nearest_tag_across_merges() -> Result<(), Error> {
+        let repo = TempRepository::new()?;
+        let root = repo.empty_commit("Root", 0, &[])?;
+        let mut near = root;
+        for time in 1..5 {
+            near = repo.empty_commit("Near", time, &[near])?;
+        }
+        // The far tag is newer, so it is walked first from the merge.
+        let far = repo.empty_commit("Far", 5, &[root])?;
+        let mut side = far;
+        for time in 6..8 {
+            side = repo.empty_commit("Side", time, &[side])?;
+        }
+        let merge = repo.empty_commit("Merge", 8, &[side, near])?;
+        repo.0.reference("refs/tags/near", near, true, "")?;
+        repo.0.reference("refs/tags/far", far, true, "")?;
+        repo.0.reference("refs/heads/master", merge, true, "")?;
+
+        let browser = Browser::new(&repo, Branch::local("master"))?;
+        let description = browser
+            .describe(Branch::local("master"))?
+            .expect("master is tagged");
+        assert_eq!(description.tag, TagName::new("near"));
+        assert_eq!(description.distance, 4);
+
+        Ok(())
+    }
+
+    #[test]
+    fn follows_namespaced_head() -> Result<(), Error> {
+        let repo = TempRepository::new()?;
+        let commit = repo.empty_commit("First", 0, &[])?;
+        repo.0.reference("refs/heads/main", commit, true, "")?;
+        repo.0.set_head("refs/heads/main")?;
+        repo.0
+            .reference("refs/namespaces/ns/refs/heads/trunk", commit, true, "")?;
+        repo.0.reference_symbolic(
+            "refs/namespaces/ns/HEAD",
+            "refs/namespaces/ns/refs/heads/trunk",
+            true,
+            "",
+        )?;
+
+        let browser = Browser::default(&repo)?;
+        assert_eq!(browser.default_branch()?, Branch::local("main"));
+
+        let browser =
+            browser.switch_namespace(&Namespace::try_from("ns")?, Branch::local("trunk"))?;
+        assert_eq!(browser.default_branch()?, Branch::local("trunk"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn compares_across_namespaces() -> Result<(), Error> {
+        let repo = TempRepository::new()?;
+        let base = repo.empty_commit("Base", 0, &[])?;
+        let ours = repo.empty_commit("Ours", 1, &[base])?;
+        let first = repo.empty_commit("First", 2, &[base])?;
+        let theirs = repo.empty_commit("Second", 3, &[first])?;
+        repo.0
+            .reference("refs/namespaces/alice/refs/heads/main", ours, true, "")?;
+        repo.0
+            .reference("refs/namespaces/bob/refs/heads/main", theirs, true, "")?;
+
+        let alice = Namespace::try_from("alice")?;
+        let browser = Browser::new_with_namespace(&repo, &alice, Branch::local("main"))?;
+
+        let ours = Ref::from(Branch::local("main")).namespaced(alice.clone());
+        let theirs_ref = Ref::from(Branch::local("main")).namespaced(Namespace::try_from("bob")?);
+
+        assert_eq!(browser.merge_base_refs(&ours, &theirs_ref)?, Some(base));
+        assert_eq!(
+            browser.ahead_behind_refs(&ours, &theirs_ref)?,
+            AheadBehind {
+                ahead: 1,
+                behind: 2
+            }
+        );
+        assert_eq!(
+            browser
+                .commits_between(&ours, &theirs_ref)?
+                .into_iter()
+                .map(|commit| commit.summary)
+                .collect::<Vec<_>>(),
+            vec!["Second", "First"]
+        );
+        assert_eq!(browser.commits_between(&ours, &theirs_ref)?[0].id, theirs);
+        assert!(browser.diff_refs(&ours, &theirs_ref)?.created.is_empty());
+        assert_eq!(browser.which_namespace()?, Some(alice));
+
+        Ok(())
+    }
+}
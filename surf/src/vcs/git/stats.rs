@@ -15,6 +15,9 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
+use crate::vcs::git::Author;
+use std::collections::{BTreeMap, HashMap};
+
 pub use git2::Oid;
 
 #[cfg(feature = "serialize")]
@@ -34,3 +37,218 @@ pub struct Stats {
     /// Number of contributors
     pub contributors: usize,
 }
+
+/// The number of seconds in a week.
+const WEEK: i64 = 7 * 24 * 60 * 60;
+
+/// The largest number of weeks the activity of [`DetailedStats`] spans with
+/// the empty weeks filled in, i.e. about a century.
+const MAX_ACTIVITY_WEEKS: i64 = 52 * 100;
+
+/// Detailed statistics for a revision, breaking down the activity per
+/// contributor and per week.
+#[cfg_attr(
+    feature = "serialize",
+    derive(Serialize),
+    serde(rename_all = "camelCase")
+)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DetailedStats {
+    /// Number of commits
+    pub commits: usize,
+    /// Number of local branches
+    pub branches: usize,
+    /// Number of local tags
+    pub tags: usize,
+    /// The contributors, ordered by the number of commits they authored,
+    /// most first.
+    pub contributors: Vec<Contributor>,
+    /// The number of commits per week, from the week of the oldest commit to
+    /// the week of the most recent one. If the commits span more than about a
+    /// century, e.g. because of bogus commit dates, only the weeks with
+    /// commits are listed.
+    pub activity: Vec<Week>,
+}
+
+/// The activity of a single contributor.
+#[cfg_attr(
+    feature = "serialize",
+    derive(Serialize),
+    serde(rename_all = "camelCase")
+)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Contributor {
+    /// Name of the contributor.
+    pub name: String,
+    /// Email of the contributor.
+    pub email: String,
+    /// Number of commits the contributor authored or co-authored.
+    pub commits: usize,
+    /// Time of the contributor's first commit, in seconds since the epoch.
+    pub first_commit: i64,
+    /// Time of the contributor's last commit, in seconds since the epoch.
+    pub last_commit: i64,
+    /// Number of lines added by the contributor's commits.
+    pub additions: usize,
+    /// Number of lines removed by the contributor's commits.
+    pub deletions: usize,
+}
+
+/// The number of commits authored within a week.
+#[cfg_attr(
+    feature = "serialize",
+    derive(Serialize),
+    serde(rename_all = "camelCase")
+)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Week {
+    /// The start of the week, i.e. Monday 00:00 UTC, in seconds since the
+    /// epoch.
+    pub start: i64,
+    /// Number of commits authored within the week.
+    pub commits: usize,
+}
+
+/// Get the start of the week, i.e. Monday 00:00 UTC, for the given time in
+/// seconds since the epoch.
+pub(crate) fn week_start(seconds: i64) -> i64 {
+    // The epoch was on a Thursday, so we shift by three days to land on the
+    // Monday of the same week.
+    let day = 24 * 60 * 60;
+    let days = seconds.div_euclid(day);
+    (days - (days + 3).rem_euclid(7)).saturating_mul(day)
+}
+
+/// Accumulates the per-contributor and per-week activity of commits.
+#[derive(Default)]
+pub(crate) struct Tally {
+    contributors: HashMap<(String, String), Contributor>,
+    weeks: BTreeMap<i64, usize>,
+}
+
+impl Tally {
+    /// Record a commit made by `authors` at `time`, in seconds since the
+    /// epoch, which added and removed the given number of lines.
+    pub(crate) fn record(
+        &mut self,
+        authors: &[Author],
+        time: i64,
+        additions: usize,
+        deletions: usize,
+    ) {
+        *self.weeks.entry(week_start(time)).or_insert(0) += 1;
+
+        for author in authors {
+            let contributor = self
+                .contributors
+                .entry((author.name.clone(), author.email.clone()))
+                .or_insert_with(|| Contributor {
+                    name: author.name.clone(),
+                    email: author.email.clone(),
+                    commits: 0,
+                    first_commit: time,
+                    last_commit: time,
+                    additions: 0,
+                    deletions: 0,
+                });
+            contributor.commits += 1;
+            contributor.first_commit = contributor.first_commit.min(time);
+            contributor.last_commit = contributor.last_commit.max(time);
+            contributor.additions += additions;
+            contributor.deletions += deletions;
+        }
+    }
+
+    /// Finish the tally, returning the ordered contributors and the weekly
+    /// activity.
+    pub(crate) fn finish(self) -> (Vec<Contributor>, Vec<Week>) {
+        let mut contributors = self.contributors.into_values().collect::<Vec<_>>();
+        contributors.sort_by(|a, b| {
+            b.commits
+                .cmp(&a.commits)
+                .then_with(|| a.name.cmp(&b.name))
+                .then_with(|| a.email.cmp(&b.email))
+        });
+
+        let mut activity = vec![];
+        if let (Some(&first), Some(&last)) =
+            (self.weeks.keys().next(), self.weeks.keys().next_back())
+        {
+            let weeks = last.checked_sub(first).map(|span| span / WEEK);
+            if matches!(weeks, Some(weeks) if weeks <= MAX_ACTIVITY_WEEKS) {
+                let mut start = Some(first);
+                while let Some(week) = start.filter(|week| *week <= last) {
+                    activity.push(Week {
+                        start: week,
+                        commits: self.weeks.get(&week).copied().unwrap_or(0),
+                    });
+                    start = week.checked_add(WEEK);
+                }
+            } else {
+                activity = self
+                    .weeks
+                    .into_iter()
+                    .map(|(start, commits)| Week { start, commits })
+                    .collect();
+            }
+        }
+
+        (contributors, activity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_week_start() {
+        // Tuesday, 11 May 2021 13:45:37 UTC
+        assert_eq!(week_start(1_620_740_737), 1_620_604_800);
+        // Monday, 10 May 2021 00:00:00 UTC
+        assert_eq!(week_start(1_620_604_800), 1_620_604_800);
+        // Sunday, 28 December 1969 00:00:00 UTC
+        assert_eq!(week_start(-345_600), -864_000);
+    }
+
+    #[test]
+    fn test_tally_fills_empty_weeks() {
+        let author = Author {
+            name: "Alice".to_string(),
+            email: "alice@example.com".to_string(),
+            time: git2::Time::new(0, 0),
+        };
+        let mut tally = Tally::default();
+        tally.record(std::slice::from_ref(&author), 1_620_740_737, 3, 1);
+        tally.record(&[author], 1_620_740_737 + 2 * WEEK, 1, 1);
+
+        let (contributors, activity) = tally.finish();
+        assert_eq!(
+            activity.iter().map(|week| week.commits).collect::<Vec<_>>(),
+            vec![1, 0, 1]
+        );
+        assert_eq!(contributors.len(), 1);
+        assert_eq!(contributors[0].commits, 2);
+        assert_eq!(contributors[0].additions, 4);
+        assert_eq!(contributors[0].deletions, 2);
+    }
+
+    #[test]
+    fn test_tally_skips_empty_weeks_of_bogus_dates() {
+        let author = Author {
+            name: "Alice".to_string(),
+            email: "alice@example.com".to_string(),
+            time: git2::Time::new(0, 0),
+        };
+        let mut tally = Tally::default();
+        tally.record(std::slice::from_ref(&author), i64::MIN, 0, 0);
+        tally.record(std::slice::from_ref(&author), 1_620_740_737, 0, 0);
+        tally.record(&[author], i64::MAX, 0, 0);
+
+        let (_, activity) = tally.finish();
+        assert_eq!(
+            activity.iter().map(|week| week.commits).collect::<Vec<_>>(),
+            vec![1, 1, 1]
+        );
+    }
+}
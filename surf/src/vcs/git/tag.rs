@@ -19,8 +19,12 @@ use crate::vcs::git::{self, error::Error, reference::Ref, Author};
 use git2::Oid;
 use std::{convert::TryFrom, fmt, str};
 
+#[cfg(feature = "serialize")]
+use serde::{Serialize, Serializer};
+
 /// A newtype wrapper over `String` to separate out the fact that a caller wants
 /// to fetch a tag.
+#[cfg_attr(feature = "serialize", derive(Serialize))]
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct TagName(String);
 
@@ -61,6 +65,49 @@ impl TagName {
     }
 }
 
+/// The type of the object a [`Tag`] points to.
+#[cfg_attr(
+    feature = "serialize",
+    derive(Serialize),
+    serde(rename_all = "lowercase")
+)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ObjectKind {
+    /// A commit, the usual target of a tag.
+    Commit,
+    /// A tree, i.e. a snapshot of a directory.
+    Tree,
+    /// A blob, e.g. a public key as tagged by `git.git`.
+    Blob,
+    /// Another annotated tag.
+    Tag,
+}
+
+impl ObjectKind {
+    pub(crate) fn of(object: &git2::Object) -> Result<Self, Error> {
+        match object.kind() {
+            Some(git2::ObjectType::Commit) => Ok(Self::Commit),
+            Some(git2::ObjectType::Tree) => Ok(Self::Tree),
+            Some(git2::ObjectType::Blob) => Ok(Self::Blob),
+            Some(git2::ObjectType::Tag) => Ok(Self::Tag),
+            _ => Err(
+                git2::Error::from_str(&format!("unknown type of object {}", object.id())).into(),
+            ),
+        }
+    }
+}
+
+impl fmt::Display for ObjectKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Commit => write!(f, "commit"),
+            Self::Tree => write!(f, "tree"),
+            Self::Blob => write!(f, "blob"),
+            Self::Tag => write!(f, "tag"),
+        }
+    }
+}
+
 /// The static information of a [`git2::Tag`].
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Tag {
@@ -68,6 +115,9 @@ pub enum Tag {
     Light {
         /// The Object ID for the `Tag`, i.e the SHA1 digest.
         id: Oid,
+        /// The type of the object that is tagged, which is never
+        /// [`ObjectKind::Tag`].
+        kind: ObjectKind,
         /// The name that references this `Tag`.
         name: TagName,
         /// If the tag is provided this holds the remote’s name.
@@ -79,6 +129,15 @@ pub enum Tag {
         id: Oid,
         /// The Object ID for the object that is tagged.
         target_id: Oid,
+        /// The type of the object that is tagged.
+        target_kind: ObjectKind,
+        /// The Object ID for the object found by following the chain of tags
+        /// starting at `target_id`. This is `target_id` itself unless the
+        /// `Tag` tags another tag.
+        peeled_id: Oid,
+        /// The type of the object found at `peeled_id`, which is never
+        /// [`ObjectKind::Tag`].
+        peeled_kind: ObjectKind,
         /// The name that references this `Tag`.
         name: TagName,
         /// The named author of this `Tag`, if the `Tag` was annotated.
@@ -90,6 +149,14 @@ pub enum Tag {
     },
 }
 
+/// The first lines of the signature blocks `git` appends to the message of
+/// signed tags, for OpenPGP, X.509 and SSH signatures respectively.
+const SIGNATURE_HEADERS: [&str; 3] = [
+    "-----BEGIN PGP SIGNATURE-----",
+    "-----BEGIN SIGNED MESSAGE-----",
+    "-----BEGIN SSH SIGNATURE-----",
+];
+
 impl Tag {
     /// Get the `Oid` of the tag, regardless of its type.
     pub fn id(&self) -> Oid {
@@ -106,19 +173,111 @@ impl Tag {
             Self::Annotated { name, .. } => name.clone(),
         }
     }
+
+    /// Get the `Oid` of the object the tag finally points to, following any
+    /// chain of nested tags.
+    pub fn peeled_id(&self) -> Oid {
+        match self {
+            Self::Light { id, .. } => *id,
+            Self::Annotated { peeled_id, .. } => *peeled_id,
+        }
+    }
+
+    /// Get the type of the object the tag finally points to, following any
+    /// chain of nested tags. Only tags of commits can be browsed.
+    pub fn peeled_kind(&self) -> ObjectKind {
+        match self {
+            Self::Light { kind, .. } => *kind,
+            Self::Annotated { peeled_kind, .. } => *peeled_kind,
+        }
+    }
+
+    /// Get the remote's name of the tag, if it was found under
+    /// `refs/remotes/<remote>/tags`.
+    pub fn remote(&self) -> Option<&str> {
+        match self {
+            Self::Light { remote, .. } => remote.as_deref(),
+            Self::Annotated { remote, .. } => remote.as_deref(),
+        }
+    }
+
+    /// Whether the tag is an annotated tag carrying a signature in its
+    /// message, as created by `git tag --sign`. The signature itself is not
+    /// verified.
+    pub fn is_signed(&self) -> bool {
+        match self {
+            Self::Light { .. } => false,
+            Self::Annotated { message, .. } => message
+                .iter()
+                .flat_map(|message| message.lines())
+                .any(|line| SIGNATURE_HEADERS.contains(&line.trim_end())),
+        }
+    }
+}
+
+/// The nearest tag reachable from a commit, as found by
+/// [`crate::vcs::git::Browser::describe`].
+///
+/// Its `Display` instance follows `git describe --tags --long`, e.g.
+/// `v0.3.0-4-g27acd68`.
+#[cfg_attr(
+    feature = "serialize",
+    derive(Serialize),
+    serde(rename_all = "camelCase")
+)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Description {
+    /// The name of the nearest tag.
+    pub tag: TagName,
+    /// Whether the nearest tag is an annotated tag.
+    pub annotated: bool,
+    /// The number of commits reachable from the described commit that are not
+    /// reachable from the tag.
+    pub distance: usize,
+    /// The `Oid` of the described commit.
+    #[cfg_attr(feature = "serialize", serde(serialize_with = "serialize_oid"))]
+    pub id: Oid,
+    /// The abbreviated, yet unambiguous, form of `id`.
+    pub short_id: String,
+}
+
+impl Description {
+    /// Whether the described commit is the tagged commit itself.
+    pub fn is_exact(&self) -> bool {
+        self.distance == 0
+    }
+}
+
+#[cfg(feature = "serialize")]
+fn serialize_oid<S>(oid: &Oid, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_str(&oid.to_string())
+}
+
+impl fmt::Display for Description {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}-g{}", self.tag, self.distance, self.short_id)
+    }
 }
 
 impl<'repo> TryFrom<git2::Tag<'repo>> for Tag {
-    type Error = str::Utf8Error;
+    type Error = Error;
 
     fn try_from(tag: git2::Tag) -> Result<Self, Self::Error> {
         let id = tag.id();
 
         let target_id = tag.target_id();
+        let target_kind = ObjectKind::of(&tag.target()?)?;
+
+        let peeled = tag.peel()?;
+        let peeled_id = peeled.id();
+        let peeled_kind = ObjectKind::of(&peeled)?;
 
         let name = TagName::try_from(tag.name_bytes())?;
 
-        let tagger = tag.tagger().map(Author::try_from).transpose()?;
+        let tagger = tag.tagger().map(Author::from);
 
         let message = tag
             .message_bytes()
@@ -129,6 +288,9 @@ impl<'repo> TryFrom<git2::Tag<'repo>> for Tag {
         Ok(Tag::Annotated {
             id,
             target_id,
+            target_kind,
+            peeled_id,
+            peeled_kind,
             name,
             tagger,
             message,
@@ -153,17 +315,28 @@ impl<'repo> TryFrom<git2::Reference<'repo>> for Tag {
         };
 
         match reference.peel_to_tag() {
-            Ok(tag) => Ok(Tag::try_from(tag)?),
+            Ok(tag) => {
+                let mut tag = Tag::try_from(tag)?;
+                if let Tag::Annotated {
+                    remote: ref mut tag_remote,
+                    ..
+                } = tag
+                {
+                    *tag_remote = remote;
+                }
+                Ok(tag)
+            },
             Err(err) => {
                 // If we get an error peeling to a tag _BUT_ we also have confirmed the
                 // reference is a tag, that means we have a lightweight tag,
-                // i.e. a commit SHA and name.
+                // i.e. the SHA of a commit, or of a tree or blob, and a name.
                 if err.class() == git2::ErrorClass::Object
                     && err.code() == git2::ErrorCode::InvalidSpec
                 {
-                    let commit = reference.peel_to_commit()?;
+                    let object = reference.peel(git2::ObjectType::Any)?;
                     Ok(Tag::Light {
-                        id: commit.id(),
+                        id: object.id(),
+                        kind: ObjectKind::of(&object)?,
                         name,
                         remote,
                     })
@@ -174,3 +347,160 @@ impl<'repo> TryFrom<git2::Reference<'repo>> for Tag {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vcs::git::{fixture::TempRepository, *};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    #[cfg(feature = "serialize")]
+    fn test_serialize_description() -> Result<(), git2::Error> {
+        let description = Description {
+            tag: TagName::new("v0.3.0"),
+            annotated: true,
+            distance: 4,
+            id: Oid::from_str("27acd68c7504755aa11023300890bb85bbd69d45")?,
+            short_id: "27acd68".to_string(),
+        };
+        assert_eq!(
+            serde_json::to_value(&description).unwrap(),
+            serde_json::json!({
+                "tag": "v0.3.0",
+                "annotated": true,
+                "distance": 4,
+                "id": "27acd68c7504755aa11023300890bb85bbd69d45",
+                "shortId": "27acd68",
+            })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn keeps_remote_of_annotated_tags() -> Result<(), Error> {
+        let repo = TempRepository::new()?;
+        let commit = repo.empty_commit("First", 0, &[])?;
+        let object = repo.0.find_object(commit, None)?;
+        let sig = git2::Signature::new("Alice", "alice@example.com", &Time::new(0, 0))?;
+        let message =
+            "Release\n-----BEGIN PGP SIGNATURE-----\n\nabc\n-----END PGP SIGNATURE-----\n";
+        let signed = repo.0.tag("v1", &object, &sig, message, false)?;
+        let plain = repo.0.tag("v2", &object, &sig, "Release", false)?;
+
+        repo.0.reference("refs/heads/master", commit, true, "")?;
+        repo.0
+            .reference("refs/remotes/peer/tags/v1", signed, true, "")?;
+        repo.0
+            .reference("refs/remotes/peer/tags/v2", plain, true, "")?;
+        repo.0.reference("refs/tags/v0", commit, true, "")?;
+
+        let browser = Browser::new(&repo, Branch::local("master"))?;
+
+        let mut tags = browser.list_tags(RefScope::Remote {
+            name: Some("peer".to_string()),
+        })?;
+        tags.sort_by_key(|tag| tag.name());
+        assert_eq!(
+            tags.iter().map(|tag| tag.name()).collect::<Vec<_>>(),
+            vec![TagName::new("v1"), TagName::new("v2")]
+        );
+        assert!(tags.iter().all(|tag| tag.remote() == Some("peer")));
+        assert!(tags[0].is_signed());
+        assert!(!tags[1].is_signed());
+
+        let mut tags = browser.list_tags(RefScope::Local)?;
+        tags.sort_by_key(|tag| tag.name());
+        assert!(tags.iter().all(|tag| tag.remote().is_none()));
+        assert_eq!(
+            tags.iter().map(Tag::is_signed).collect::<Vec<_>>(),
+            vec![false, true, false]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn lists_tags_of_any_object() -> Result<(), Error> {
+        let repo = TempRepository::new()?;
+        let (commit, tree, blob, nested) = {
+            let repo = &repo.0;
+            let sig = git2::Signature::new("Alice", "alice@example.com", &Time::new(0, 0))?;
+            let blob = repo.blob(b"-----BEGIN PGP PUBLIC KEY BLOCK-----")?;
+            let mut builder = repo.treebuilder(None)?;
+            builder.insert("key.asc", blob, 0o100644)?;
+            let tree = builder.write()?;
+            let commit = repo.commit(None, &sig, &sig, "First", &repo.find_tree(tree)?, &[])?;
+            repo.reference("refs/heads/master", commit, true, "")?;
+
+            repo.reference("refs/tags/key", blob, true, "")?;
+            repo.tag(
+                "snapshot",
+                &repo.find_object(tree, None)?,
+                &sig,
+                "Snapshot",
+                false,
+            )?;
+            let inner = repo.tag(
+                "inner",
+                &repo.find_object(commit, None)?,
+                &sig,
+                "Inner",
+                false,
+            )?;
+            let nested = repo.tag(
+                "release",
+                &repo.find_object(inner, None)?,
+                &sig,
+                "Release",
+                false,
+            )?;
+            (commit, tree, blob, nested)
+        };
+
+        let mut browser = Browser::new(&repo, Branch::local("master"))?;
+
+        let mut tags = browser.list_tags(RefScope::Local)?;
+        tags.sort_by_key(|tag| tag.name());
+        assert_eq!(
+            tags.iter()
+                .map(|tag| (tag.name().to_string(), tag.peeled_id(), tag.peeled_kind()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("inner".to_string(), commit, ObjectKind::Commit),
+                ("key".to_string(), blob, ObjectKind::Blob),
+                ("release".to_string(), commit, ObjectKind::Commit),
+                ("snapshot".to_string(), tree, ObjectKind::Tree),
+            ]
+        );
+        match &tags[2] {
+            Tag::Annotated {
+                id, target_kind, ..
+            } => {
+                assert_eq!(*id, nested);
+                assert_eq!(*target_kind, ObjectKind::Tag);
+            },
+            tag => panic!("expected an annotated tag, got {:?}", tag),
+        }
+
+        assert_eq!(
+            browser.tag(TagName::new("snapshot")),
+            Err(Error::TagNotCommit {
+                name: TagName::new("snapshot"),
+                kind: ObjectKind::Tree,
+            })
+        );
+        browser.tag(TagName::new("release"))?;
+        assert_eq!(browser.get().first().id, commit);
+
+        let mut tags = browser.revision_tags(commit)?;
+        tags.sort_by_key(|tag| tag.name());
+        assert_eq!(
+            tags.iter().map(|tag| tag.name()).collect::<Vec<_>>(),
+            vec![TagName::new("inner"), TagName::new("release")]
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,882 @@
+// This file is part of radicle-surf
+// <https://github.com/radicle-dev/radicle-surf>
+//
+// Copyright (C) 2019-2020 The Radicle Team <dev@radicle.xyz>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 or
+// later as published by the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! An experimental backend reading git repositories with the pure-Rust
+//! [`gix`](::gix) crate, enabled by the `gix` feature.
+//!
+//! It implements the generic [`vcs::Browser`] and [`Vcs`] surface, the
+//! history of a reference and the [`Directory`] of the commit a history
+//! starts at, along with the [`Diff`] of two commits, the last commit of a
+//! path, and the branches, tags and namespaces of a repository. Building
+//! without the default `git2` feature drops the libgit2 C library.
+//!
+//! Everything else, e.g. blame, notes or the statistics of a repository, is
+//! only provided by `vcs::git`. The hunks of a [`Diff`] are computed by
+//! `radicle-surf` itself, and may align ambiguous changes differently than
+//! `git`.
+//!
+//! # Examples
+//!
+//! ```
+//! use radicle_surf::{
+//!     file_system::unsound,
+//!     vcs::gix::{Browser, Repository},
+//! };
+//! # use std::error::Error;
+//!
+//! # fn main() -> Result<(), Box<dyn Error>> {
+//! let repo = Repository::new("./data/git-platinum")?;
+//! let browser = Browser::new(repo, "refs/heads/master")?;
+//!
+//! let history = browser.get();
+//! assert_eq!(
+//!     history.first().summary,
+//!     "Add files with special characters in their filenames (#5)"
+//! );
+//! assert_eq!(history.len(), 15);
+//!
+//! let directory = browser.get_directory()?;
+//! assert!(directory.find_file(unsound::path::new("src/memory.rs")).is_some());
+//! #
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{
+    diff::{lines, Diff, FileDiff, Hunks},
+    file_system::{self, Directory, File, Label},
+    vcs::{self, Vcs},
+};
+use ::gix::{
+    bstr::ByteSlice as _, objs::tree::EntryMode, revision::walk::Sorting,
+    traverse::commit::simple::CommitTimeOrder,
+};
+use nonempty::NonEmpty;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    convert::TryFrom,
+    path::{Path, PathBuf},
+    str,
+};
+use thiserror::Error;
+
+pub use ::gix::ObjectId;
+
+/// Enumeration of errors that can occur in operations from
+/// [`crate::vcs::gix`].
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum Error {
+    /// The reference to browse does not exist.
+    #[error("provided reference does not exist: {0}")]
+    NotFound(String),
+    /// The name of a file or directory is not a valid [`Label`].
+    #[error(transparent)]
+    FileSystem(#[from] file_system::Error),
+    /// The name of a file or directory is not valid UTF-8.
+    #[error(transparent)]
+    Utf8Error(#[from] str::Utf8Error),
+    /// An error reported by `gix`.
+    #[error(transparent)]
+    Gix(#[from] ::gix::Error),
+}
+
+/// The signature of the author of a [`Commit`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Author {
+    /// Name of the author.
+    pub name: String,
+    /// Email of the author.
+    pub email: String,
+    /// Seconds since the Unix epoch at which the commit was authored.
+    pub time: i64,
+}
+
+/// The static information of a commit, as read by `gix`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Commit {
+    /// Object ID of the commit.
+    pub id: ObjectId,
+    /// The author of the commit.
+    pub author: Author,
+    /// The full commit message.
+    pub message: String,
+    /// The first line of the commit message.
+    pub summary: String,
+    /// The parents of the commit.
+    pub parents: Vec<ObjectId>,
+}
+
+impl<'repo> TryFrom<::gix::Commit<'repo>> for Commit {
+    type Error = Error;
+
+    fn try_from(commit: ::gix::Commit<'repo>) -> Result<Self, Self::Error> {
+        let author = commit.author()?;
+        let message = commit.message_raw()?;
+        Ok(Self {
+            id: commit.id,
+            author: Author {
+                name: author.name.to_str_lossy().into_owned(),
+                email: author.email.to_str_lossy().into_owned(),
+                time: author.time()?.seconds,
+            },
+            message: message.to_str_lossy().into_owned(),
+            summary: commit.message()?.summary().to_str_lossy().into_owned(),
+            parents: commit.parent_ids().map(|id| id.detach()).collect(),
+        })
+    }
+}
+
+/// A branch of a repository.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Branch {
+    /// The name of the branch, e.g. `master`.
+    pub name: String,
+    /// The remote of a remote branch, e.g. `origin` for
+    /// `refs/remotes/origin/master`.
+    pub remote: Option<String>,
+}
+
+/// A tag of a repository.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tag {
+    /// The name of the tag, e.g. `v0.1.0`.
+    pub name: String,
+    /// The object the tag points to, after following annotated tags.
+    pub target: ObjectId,
+    /// The message of an annotated tag.
+    pub message: Option<String>,
+}
+
+/// A history of commits, starting at the most recent one.
+pub type History = vcs::History<Commit>;
+
+/// A [`vcs::Browser`] of a repository read by `gix`.
+pub type Browser = vcs::Browser<Repository, Commit, Error>;
+
+impl Browser {
+    /// Create a `Browser` of the history of `reference`, e.g.
+    /// `refs/heads/master` or `HEAD`.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::NotFound`]
+    /// * [`Error::Gix`]
+    pub fn new(repository: Repository, reference: &str) -> Result<Self, Error> {
+        let history = repository.get_history(reference.to_string())?;
+        let snapshot = Box::new(|repository: &Repository, history: &History| {
+            repository.directory(history.first().id)
+        });
+        Ok(vcs::Browser {
+            snapshot,
+            history,
+            repository,
+        })
+    }
+
+    /// Switch to `namespace`, e.g. `golden` or `golden/silver`, and browse the
+    /// history of `reference` within it.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::NotFound`]
+    /// * [`Error::Gix`]
+    pub fn switch_namespace(mut self, namespace: &str, reference: &str) -> Result<Self, Error> {
+        self.repository.0.set_namespace(namespace)?;
+        self.history = self.repository.get_history(reference.to_string())?;
+        Ok(self)
+    }
+
+    /// The namespace the `Browser` is in, if any.
+    pub fn which_namespace(&self) -> Option<String> {
+        self.repository.namespace()
+    }
+
+    /// The namespaces directly within the current namespace, or within the
+    /// repository if the `Browser` is not in a namespace, sorted by name.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Gix`]
+    pub fn list_namespaces(&self) -> Result<Vec<String>, Error> {
+        self.repository.list_namespaces()
+    }
+
+    /// The local and remote branches within the current namespace, sorted by
+    /// remote and name.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Gix`]
+    pub fn list_branches(&self) -> Result<Vec<Branch>, Error> {
+        self.repository.list_branches()
+    }
+
+    /// The tags within the current namespace, sorted by name.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Gix`]
+    pub fn list_tags(&self) -> Result<Vec<Tag>, Error> {
+        self.repository.list_tags()
+    }
+
+    /// The [`Diff`] of the commits `from` and `to`.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::FileSystem`], if a name is not a valid [`Label`]
+    /// * [`Error::Gix`]
+    pub fn diff(&self, from: ObjectId, to: ObjectId) -> Result<Diff, Error> {
+        self.repository.diff(Some(from), to)
+    }
+
+    /// The [`Diff`] of a commit with no parents.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::FileSystem`], if a name is not a valid [`Label`]
+    /// * [`Error::Gix`]
+    pub fn initial_diff(&self, id: ObjectId) -> Result<Diff, Error> {
+        self.repository.diff(None, id)
+    }
+
+    /// The most recent commit of the history which changed `path`, compared
+    /// to its first parent.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Gix`]
+    pub fn last_commit(&self, path: file_system::Path) -> Result<Option<Commit>, Error> {
+        self.repository.last_commit(&path, self.get().first().id)
+    }
+}
+
+/// An entry of a tree, as compared by [`Repository::diff`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Entry {
+    Tree(ObjectId),
+    Blob(ObjectId, EntryMode),
+}
+
+/// A git repository read by `gix`.
+pub struct Repository(::gix::Repository);
+
+impl Repository {
+    /// Open the git repository at `path`.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Gix`]
+    pub fn new(path: impl AsRef<Path>) -> Result<Self, Error> {
+        Ok(Repository(::gix::open(path.as_ref())?))
+    }
+
+    /// The [`Directory`] of the tree of the commit `id`. As for
+    /// [`crate::vcs::git`], submodules are skipped.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::FileSystem`], if a name is not a valid [`Label`]
+    /// * [`Error::Gix`]
+    pub fn directory(&self, id: ObjectId) -> Result<Directory, Error> {
+        let tree = self.0.find_commit(id)?.tree()?;
+        let mut directory = Directory::root();
+        self.insert_tree(&mut directory, &[], &tree)?;
+        Ok(directory)
+    }
+
+    fn insert_tree(
+        &self,
+        directory: &mut Directory,
+        prefix: &[Label],
+        tree: &::gix::Tree<'_>,
+    ) -> Result<(), Error> {
+        for entry in tree.iter() {
+            let entry = entry?;
+            let mut path = prefix.to_vec();
+            path.push(Label::try_from(str::from_utf8(entry.filename())?)?);
+
+            if entry.mode().is_tree() {
+                let sub_tree = entry.object()?.into_tree();
+                self.insert_tree(directory, &path, &sub_tree)?;
+            } else if entry.mode().is_blob() || entry.mode().is_link() {
+                let blob = entry.object()?;
+                let path = NonEmpty::from_slice(&path).expect("the path has a file name");
+                directory.insert_file(file_system::Path(path), File::new(&blob.data));
+            }
+        }
+        Ok(())
+    }
+
+    fn namespace(&self) -> Option<String> {
+        let namespace = self.0.namespace()?.as_bstr().to_str_lossy();
+        let namespace = namespace
+            .trim_end_matches('/')
+            .split("refs/namespaces/")
+            .filter(|name| !name.is_empty())
+            .map(|name| name.trim_end_matches('/'))
+            .collect::<Vec<_>>();
+        Some(namespace.join("/"))
+    }
+
+    fn list_namespaces(&self) -> Result<Vec<String>, Error> {
+        let references = self.0.references()?;
+        let mut namespaces = BTreeSet::new();
+        for reference in references.prefixed(b"refs/namespaces/")? {
+            let reference = reference?;
+            let name = reference.name().as_bstr().to_str_lossy();
+            if let Some(namespace) = name
+                .strip_prefix("refs/namespaces/")
+                .and_then(|name| name.split('/').next())
+            {
+                namespaces.insert(namespace.to_string());
+            }
+        }
+        Ok(namespaces.into_iter().collect())
+    }
+
+    fn list_branches(&self) -> Result<Vec<Branch>, Error> {
+        let references = self.0.references()?;
+        let mut branches = Vec::new();
+        for reference in references.local_branches()? {
+            let reference = reference?;
+            let name = reference.name().as_bstr().to_str_lossy();
+            if let Some(name) = name.strip_prefix("refs/heads/") {
+                branches.push(Branch {
+                    name: name.to_string(),
+                    remote: None,
+                });
+            }
+        }
+        for reference in references.remote_branches()? {
+            let reference = reference?;
+            let name = reference.name().as_bstr().to_str_lossy();
+            let (remote, name) = match name
+                .strip_prefix("refs/remotes/")
+                .and_then(|name| name.split_once('/'))
+            {
+                Some(remote_name) => remote_name,
+                None => continue,
+            };
+            // The tags of a remote are not its branches, and its branches may
+            // be kept under `heads/`, which git2 leaves out of their names too.
+            if name.starts_with("tags/") {
+                continue;
+            }
+            branches.push(Branch {
+                name: name.strip_prefix("heads/").unwrap_or(name).to_string(),
+                remote: Some(remote.to_string()),
+            });
+        }
+        branches.sort_by(|a, b| (&a.remote, &a.name).cmp(&(&b.remote, &b.name)));
+        Ok(branches)
+    }
+
+    fn list_tags(&self) -> Result<Vec<Tag>, Error> {
+        let references = self.0.references()?;
+        let mut tags = Vec::new();
+        for reference in references.tags()? {
+            let mut reference = reference?;
+            let name = match reference
+                .name()
+                .as_bstr()
+                .to_str_lossy()
+                .strip_prefix("refs/tags/")
+            {
+                Some(name) => name.to_string(),
+                None => continue,
+            };
+            let message = match reference.try_id() {
+                Some(id) => match id.object()?.try_into_tag() {
+                    Ok(tag) => Some(tag.decode()?.message.to_str_lossy().into_owned()),
+                    Err(_) => None,
+                },
+                None => None,
+            };
+            let target = reference.peel_to_id()?.detach();
+            tags.push(Tag {
+                name,
+                target,
+                message,
+            });
+        }
+        tags.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(tags)
+    }
+
+    /// The [`Diff`] of the trees of the commits `from`, if any, and `to`.
+    fn diff(&self, from: Option<ObjectId>, to: ObjectId) -> Result<Diff, Error> {
+        let old = match from {
+            Some(from) => Some(self.0.find_commit(from)?.tree_id()?.detach()),
+            None => None,
+        };
+        let new = self.0.find_commit(to)?.tree_id()?.detach();
+        let mut changes = BTreeMap::new();
+        self.changes(old, Some(new), "", &mut changes)?;
+
+        let mut diff = Diff::new();
+        for (path, (old, new)) in changes {
+            let path = file_system::Path::try_from(PathBuf::from(path))?;
+            match (old, new) {
+                (None, Some(new)) => {
+                    let hunks = self.hunks(None, Some(new))?;
+                    diff.add_created_file(path, FileDiff::Plain { hunks });
+                },
+                (Some(old), None) => {
+                    let hunks = self.hunks(Some(old), None)?;
+                    diff.add_deleted_file(path, FileDiff::Plain { hunks });
+                },
+                (Some(old), Some(new)) => {
+                    let old = self.0.find_blob(old)?;
+                    let new = self.0.find_blob(new)?;
+                    if lines::is_binary(&old.data) || lines::is_binary(&new.data) {
+                        diff.add_modified_binary_file(path);
+                    } else {
+                        let (hunks, eof) = lines::hunks(&old.data, &new.data);
+                        diff.add_modified_file(path, hunks, eof);
+                    }
+                },
+                (None, None) => {},
+            }
+        }
+        Ok(diff)
+    }
+
+    /// The hunks of a created or deleted file, which are empty for binary
+    /// files.
+    fn hunks(&self, old: Option<ObjectId>, new: Option<ObjectId>) -> Result<Hunks, Error> {
+        let content = |id: Option<ObjectId>| -> Result<Vec<u8>, Error> {
+            match id {
+                Some(id) => Ok(self.0.find_blob(id)?.take_data()),
+                None => Ok(Vec::new()),
+            }
+        };
+        let (old, new) = (content(old)?, content(new)?);
+        if lines::is_binary(&old) || lines::is_binary(&new) {
+            return Ok(Hunks::default());
+        }
+        Ok(lines::hunks(&old, &new).0)
+    }
+
+    /// Collect the files which differ between the trees `old` and `new`
+    /// under `prefix`, along with their old and new blobs. Submodules are
+    /// skipped.
+    fn changes(
+        &self,
+        old: Option<ObjectId>,
+        new: Option<ObjectId>,
+        prefix: &str,
+        changes: &mut BTreeMap<String, (Option<ObjectId>, Option<ObjectId>)>,
+    ) -> Result<(), Error> {
+        let old = self.entries(old)?;
+        let new = self.entries(new)?;
+        let names = old.keys().chain(new.keys()).collect::<BTreeSet<_>>();
+
+        for name in names {
+            let (old, new) = (old.get(name).copied(), new.get(name).copied());
+            if old == new {
+                continue;
+            }
+            let path = if prefix.is_empty() {
+                name.clone()
+            } else {
+                format!("{}/{}", prefix, name)
+            };
+            let tree = |entry| match entry {
+                Some(Entry::Tree(id)) => Some(id),
+                _ => None,
+            };
+            let blob = |entry| match entry {
+                Some(Entry::Blob(id, _)) => Some(id),
+                _ => None,
+            };
+            if tree(old).is_some() || tree(new).is_some() {
+                self.changes(tree(old), tree(new), &path, changes)?;
+            }
+            if blob(old).is_some() || blob(new).is_some() {
+                changes.insert(path, (blob(old), blob(new)));
+            }
+        }
+        Ok(())
+    }
+
+    fn entries(&self, tree: Option<ObjectId>) -> Result<BTreeMap<String, Entry>, Error> {
+        let mut entries = BTreeMap::new();
+        let tree = match tree {
+            Some(tree) => self.0.find_tree(tree)?,
+            None => return Ok(entries),
+        };
+        for entry in tree.iter() {
+            let entry = entry?;
+            let name = str::from_utf8(entry.filename())?.to_string();
+            let id = entry.object_id();
+            if entry.mode().is_tree() {
+                entries.insert(name, Entry::Tree(id));
+            } else if entry.mode().is_blob() || entry.mode().is_link() {
+                entries.insert(name, Entry::Blob(id, entry.mode()));
+            }
+        }
+        Ok(entries)
+    }
+
+    /// The most recent commit reachable from `head` whose entry at `path`
+    /// differs from the one of its first parent.
+    fn last_commit(
+        &self,
+        path: &file_system::Path,
+        head: ObjectId,
+    ) -> Result<Option<Commit>, Error> {
+        let components = path
+            .iter()
+            .filter(|label| !label.is_root())
+            .map(|label| label.to_string())
+            .collect::<Vec<_>>();
+        let walk = self
+            .0
+            .rev_walk([head])
+            .sorting(Sorting::ByCommitTime(CommitTimeOrder::NewestFirst))
+            .all()?;
+        for info in walk {
+            let commit = self.0.find_commit(info?.id)?;
+            let entry = self.entry(Some(commit.tree_id()?.detach()), &components)?;
+            let parent = match commit.parent_ids().next() {
+                Some(parent) => Some(self.0.find_commit(parent)?.tree_id()?.detach()),
+                None => None,
+            };
+            if entry != self.entry(parent, &components)? {
+                return Ok(Some(Commit::try_from(commit)?));
+            }
+        }
+        Ok(None)
+    }
+
+    /// The object and mode of the entry at `path` in `tree`, if any.
+    fn entry(
+        &self,
+        tree: Option<ObjectId>,
+        path: &[String],
+    ) -> Result<Option<(ObjectId, EntryMode)>, Error> {
+        let tree = match tree {
+            Some(tree) => self.0.find_tree(tree)?,
+            None => return Ok(None),
+        };
+        if path.is_empty() {
+            return Ok(Some((
+                tree.id,
+                EntryMode::from(::gix::objs::tree::EntryKind::Tree),
+            )));
+        }
+        Ok(tree
+            .lookup_entry_by_path(path.join("/"))?
+            .map(|entry| (entry.object_id(), entry.mode())))
+    }
+
+    fn history_of(&self, id: ObjectId) -> Result<History, Error> {
+        let mut commits = Vec::new();
+        let walk = self
+            .0
+            .rev_walk([id])
+            .sorting(Sorting::ByCommitTime(CommitTimeOrder::NewestFirst))
+            .all()?;
+        for info in walk {
+            commits.push(Commit::try_from(self.0.find_commit(info?.id)?)?);
+        }
+        let commits = NonEmpty::from_slice(&commits).expect("a history starts at a commit");
+        Ok(vcs::History(commits))
+    }
+}
+
+impl Vcs<Commit, Error> for Repository {
+    /// The full name of a reference, or `HEAD`.
+    type HistoryId = String;
+    type ArtefactId = ObjectId;
+
+    fn get_history(&self, identifier: Self::HistoryId) -> Result<History, Error> {
+        let mut reference = self
+            .0
+            .try_find_reference(identifier.as_str())?
+            .ok_or(Error::NotFound(identifier))?;
+        let id = reference.peel_to_commit()?.id;
+        self.history_of(id)
+    }
+
+    fn get_histories(&self) -> Result<Vec<History>, Error> {
+        let references = self.0.references()?;
+        let mut histories = Vec::new();
+        for reference in references.local_branches()? {
+            let mut reference = reference?;
+            histories.push(self.history_of(reference.peel_to_commit()?.id)?);
+        }
+        Ok(histories)
+    }
+
+    fn get_identifier(artifact: &Commit) -> Self::ArtefactId {
+        artifact.id
+    }
+}
+
+impl std::fmt::Debug for Repository {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "gix::Repository({})", self.0.path().display())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Branch, Browser, Error, Repository};
+    #[cfg(feature = "git2")]
+    use crate::{
+        file_system::{self, unsound},
+        vcs::git,
+    };
+    use pretty_assertions::assert_eq;
+    #[cfg(feature = "git2")]
+    use std::convert::TryFrom;
+
+    #[test]
+    #[cfg(feature = "git2")]
+    fn agrees_with_git2() -> Result<(), Error> {
+        let gix = Browser::new(Repository::new("./data/git-platinum")?, "refs/heads/dev")?;
+        let repo = git::Repository::new("./data/git-platinum").expect("failed to open");
+        let git2 = git::Browser::new(&repo, git::Branch::local("dev")).expect("failed to browse");
+
+        assert_eq!(
+            gix.get()
+                .iter()
+                .map(|commit| commit.id.to_string())
+                .collect::<Vec<_>>(),
+            git2.get()
+                .iter()
+                .map(|commit| commit.id.to_string())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            gix.get_directory()?,
+            git2.get_directory().expect("failed to get the directory")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "git2")]
+    fn diffs_agree_with_git2() -> Result<(), Error> {
+        let gix = Browser::new(Repository::new("./data/git-platinum")?, "refs/heads/dev")?;
+        let repo = git::Repository::new("./data/git-platinum").expect("failed to open");
+        let git2 = git::Browser::new(&repo, git::Branch::local("dev")).expect("failed to browse");
+
+        for commit in gix.get().iter() {
+            let to = git::Oid::from_bytes(commit.id.as_bytes()).expect("invalid oid");
+            let (ours, theirs) = match commit.parents.first() {
+                Some(parent) => (
+                    gix.diff(*parent, commit.id)?,
+                    git2.diff(
+                        git::Oid::from_bytes(parent.as_bytes()).expect("invalid oid"),
+                        to,
+                    )
+                    .expect("failed to diff"),
+                ),
+                None => (
+                    gix.initial_diff(commit.id)?,
+                    git2.initial_diff(to).expect("failed to diff"),
+                ),
+            };
+            assert_eq!(ours, theirs, "the diffs of {} differ", commit.id);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "git2")]
+    fn last_commits_agree_with_git2() -> Result<(), Error> {
+        let gix = Browser::new(Repository::new("./data/git-platinum")?, "refs/heads/dev")?;
+        let repo = git::Repository::new("./data/git-platinum").expect("failed to open");
+        let git2 = git::Browser::new(&repo, git::Branch::local("dev")).expect("failed to browse");
+
+        for path in &[
+            "",
+            "README.md",
+            "src/memory.rs",
+            "this/is/a/really/deeply/nested/directory/tree",
+            "missing",
+        ] {
+            let labels = path
+                .split('/')
+                .filter(|label| !label.is_empty())
+                .map(unsound::label::new)
+                .collect::<Vec<_>>();
+            let ours = gix
+                .last_commit(file_system::Path::with_root(&labels))?
+                .map(|commit| commit.id.to_string());
+            let theirs = git2
+                .last_commit(file_system::Path::with_root(&labels))
+                .expect("failed to get the last commit")
+                .map(|commit| commit.id.to_string());
+            assert_eq!(ours, theirs, "the last commits of /{} differ", path);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn lists_branches_and_tags() -> Result<(), Error> {
+        let gix = Browser::new(Repository::new("./data/git-platinum")?, "refs/heads/dev")?;
+        let branch = |name: &str, remote: Option<&str>| Branch {
+            name: name.to_string(),
+            remote: remote.map(str::to_string),
+        };
+
+        assert_eq!(
+            gix.list_branches()?,
+            vec![
+                branch("dev", None),
+                branch("master", None),
+                branch("orange/pineapple", Some("banana")),
+                branch("pineapple", Some("banana")),
+                branch("HEAD", Some("origin")),
+                branch("dev", Some("origin")),
+                branch("master", Some("origin")),
+            ]
+        );
+
+        let tags = gix.list_tags()?;
+        assert_eq!(
+            tags.iter().map(|tag| tag.name.as_str()).collect::<Vec<_>>(),
+            vec!["v0.1.0", "v0.2.0", "v0.3.0", "v0.4.0", "v0.5.0", "v0.6.0"]
+        );
+        assert!(tags[..5].iter().all(|tag| tag.message.is_none()));
+        assert!(tags[5].message.is_some());
+        assert_eq!(
+            tags[0].target.to_string(),
+            "d3464e33d75c75c99bfb90fa2e9d16efc0b7d0e3"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "git2")]
+    fn tags_agree_with_git2() -> Result<(), Error> {
+        let gix = Browser::new(Repository::new("./data/git-platinum")?, "refs/heads/dev")?;
+        let repo = git::Repository::new("./data/git-platinum").expect("failed to open");
+        let git2 = git::Browser::new(&repo, git::Branch::local("dev")).expect("failed to browse");
+
+        let ours = gix
+            .list_tags()?
+            .into_iter()
+            .map(|tag| (tag.name, tag.target.to_string(), tag.message))
+            .collect::<Vec<_>>();
+        let theirs = git2
+            .list_tags(git::RefScope::Local)
+            .expect("failed to list tags")
+            .into_iter()
+            .map(|tag| {
+                let message = match &tag {
+                    git::Tag::Light { .. } => None,
+                    git::Tag::Annotated { message, .. } => message.clone(),
+                };
+                (
+                    tag.name().name().to_string(),
+                    tag.peeled_id().to_string(),
+                    message,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(ours, theirs);
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "git2")]
+    fn branches_agree_with_git2() -> Result<(), Error> {
+        let gix = Browser::new(Repository::new("./data/git-platinum")?, "refs/heads/dev")?;
+        let repo = git::Repository::new("./data/git-platinum").expect("failed to open");
+        let git2 = git::Browser::new(&repo, git::Branch::local("dev")).expect("failed to browse");
+        // git2 lists the tags of remotes as their branches as well, which gix
+        // leaves to the tags.
+        let branches = |git2: &git::Browser| {
+            let remote_tags = git2
+                .list_tags(git::RefScope::Remote { name: None })
+                .expect("failed to list tags")
+                .into_iter()
+                .map(|tag| Branch {
+                    name: tag.name().to_string(),
+                    remote: tag.remote().map(str::to_string),
+                })
+                .collect::<Vec<_>>();
+            let mut branches = git2
+                .list_branches(git::RefScope::All)
+                .expect("failed to list branches")
+                .into_iter()
+                .map(|branch| Branch {
+                    name: branch.name.name().to_string(),
+                    remote: match branch.locality {
+                        git::BranchType::Local => None,
+                        git::BranchType::Remote { name } => name,
+                    },
+                })
+                .filter(|branch| !remote_tags.contains(branch))
+                .collect::<Vec<_>>();
+            branches.sort_by(|a, b| (&a.remote, &a.name).cmp(&(&b.remote, &b.name)));
+            branches
+        };
+
+        assert_eq!(gix.list_branches()?, branches(&git2));
+
+        let gix = gix.switch_namespace("golden", "refs/heads/banana")?;
+        let git2 = git2
+            .switch_namespace(
+                &git::Namespace::try_from("golden").expect("invalid namespace"),
+                git::Branch::local("banana"),
+            )
+            .expect("failed to switch namespace");
+        assert_eq!(gix.list_branches()?, branches(&git2));
+
+        Ok(())
+    }
+
+    #[test]
+    fn browses_namespaces() -> Result<(), Error> {
+        let gix = Browser::new(Repository::new("./data/git-platinum")?, "refs/heads/dev")?;
+        assert_eq!(gix.which_namespace(), None);
+        assert_eq!(gix.list_namespaces()?, vec!["golden", "me"]);
+
+        let gix = gix.switch_namespace("golden", "refs/heads/banana")?;
+        assert_eq!(gix.which_namespace(), Some("golden".to_string()));
+        assert_eq!(gix.list_namespaces()?, vec!["silver"]);
+        assert_eq!(
+            gix.get().first().id.to_string(),
+            "27acd68c7504755aa11023300890bb85bbd69d45"
+        );
+        assert_eq!(
+            gix.list_tags()?
+                .into_iter()
+                .map(|tag| tag.name)
+                .collect::<Vec<_>>(),
+            vec!["v0.1.0", "v0.2.0"]
+        );
+
+        let gix = gix.switch_namespace("golden/silver", "refs/heads/master")?;
+        assert_eq!(gix.which_namespace(), Some("golden/silver".to_string()));
+        assert_eq!(gix.list_namespaces()?, Vec::<String>::new());
+
+        Ok(())
+    }
+}